@@ -16,6 +16,50 @@ void startLogger(
     const char*                 log_file_path
 );
 
+//---------------------------------------------------------
+// Load the Lens Profile Library (if not already loaded):
+//---------------------------------------------------------
+int loadLensLibrary(
+    void
+);
+
+//---------------------------------------------------------
+// Reload the Lens Profile Library from disk:
+//---------------------------------------------------------
+int reloadLensLibrary(
+    void
+);
+
+//---------------------------------------------------------
+// Add a user Lens Profile directory or file:
+//---------------------------------------------------------
+const char* addUserLensProfilePath(
+    const char*                 path
+);
+
+//---------------------------------------------------------
+// Remove all the user Lens Profile directories & files:
+//---------------------------------------------------------
+int clearUserLensProfilePaths(
+    void
+);
+
+//---------------------------------------------------------
+// List the Lens Profiles in the Lens Profile Library:
+//---------------------------------------------------------
+const char* getLensProfiles(
+    const char*                 filter_json
+);
+
+//---------------------------------------------------------
+// Fuzzy search the Lens Profile Library:
+//---------------------------------------------------------
+const char* searchLensProfiles(
+    const char*                 query,
+    const char*                 filter_json,
+    uint32_t                    max_results
+);
+
 //---------------------------------------------------------
 // Process a frame:
 //---------------------------------------------------------
@@ -39,6 +83,58 @@ int processFrame(
     double                      video_rotation,
     uint8_t                     fov_overview,
     uint8_t                     disable_gyroflow_stretch,
+    uint8_t                     rolling_shutter_correction,
+    double                      frame_readout_time,
+    uint8_t                     frame_readout_direction,
+    uint8_t                     adaptive_zoom_mode,
+    double                      adaptive_zoom_window,
+    double                      max_zoom,
+    double                      light_refraction_coefficient,
+    double                      additional_rotation_pitch,
+    double                      additional_rotation_yaw,
+    double                      additional_rotation_roll,
+    double                      additional_translation_x,
+    double                      additional_translation_y,
+    double                      additional_translation_z,
+    void                        *in_mtl_texture,
+    void                        *out_mtl_texture,
+    void                        *command_queue
+);
+
+//---------------------------------------------------------
+// Process a frame with lens correction only:
+//---------------------------------------------------------
+int processLensCorrectionFrame(
+    const char*                 unique_identifier,
+    uint32_t                    width,
+    uint32_t                    height,
+    const char*                 pixel_format,
+    int                         number_of_bytes,
+    const char*                 lens_profile,
+    double                      fps,
+    double                      duration_ms,
+    int64_t                     timestamp,
+    double                      fov,
+    double                      lens_correction,
+    double                      light_refraction_coefficient,
+    double                      input_rotation,
+    uint8_t                     disable_gyroflow_stretch,
+    void                        *in_mtl_texture,
+    void                        *out_mtl_texture,
+    void                        *command_queue
+);
+
+//---------------------------------------------------------
+// Re-distort a frame to match the lens of a Gyroflow Project:
+//---------------------------------------------------------
+int processRedistortFrame(
+    const char*                 unique_identifier,
+    const char*                 path,
+    const char*                 data,
+    int64_t                     timestamp,
+    uint8_t                     apply_camera_motion,
+    double                      light_refraction_coefficient,
+    double                      input_rotation,
     void                        *in_mtl_texture,
     void                        *out_mtl_texture,
     void                        *command_queue
@@ -56,7 +152,19 @@ const char* getDefaultValues(
     double* horizon_roll,
     double* position_offset_x,
     double* position_offset_y,
-    double* video_rotation
+    double* video_rotation,
+    double* frame_readout_time,
+    uint8_t* frame_readout_direction,
+    uint8_t* adaptive_zoom_mode,
+    double* adaptive_zoom_window,
+    double* max_zoom,
+    double* light_refraction_coefficient,
+    double* additional_rotation_pitch,
+    double* additional_rotation_yaw,
+    double* additional_rotation_roll,
+    double* additional_translation_x,
+    double* additional_translation_y,
+    double* additional_translation_z
 );
 
 //---------------------------------------------------------
@@ -66,6 +174,48 @@ uint32_t trashCache(
     void
 );
 
+//---------------------------------------------------------
+// Trash the cache entries of a single instance:
+//---------------------------------------------------------
+uint32_t trashCacheForUniqueIdentifier(
+    const char*                 unique_identifier
+);
+
+//---------------------------------------------------------
+// Trash the cache entries of a Gyroflow Project path:
+//---------------------------------------------------------
+uint32_t trashCacheForProjectPath(
+    const char*                 path
+);
+
+//---------------------------------------------------------
+// Trash the cache entries of a Gyroflow Project's data:
+//---------------------------------------------------------
+uint32_t trashCacheForProjectData(
+    const char*                 gyroflow_project_data
+);
+
+//---------------------------------------------------------
+// Set the maximum number of entries in the cache:
+//---------------------------------------------------------
+uint32_t setCacheCapacity(
+    uint32_t                    capacity
+);
+
+//---------------------------------------------------------
+// Set the memory budget of the cache (0 for no budget):
+//---------------------------------------------------------
+uint32_t setCacheMemoryBudget(
+    uint64_t                    memory_budget_mb
+);
+
+//---------------------------------------------------------
+// Get the cache entries & statistics as JSON:
+//---------------------------------------------------------
+const char* getCacheStatistics(
+    void
+);
+
 //---------------------------------------------------------
 // Import Media File:
 //---------------------------------------------------------
@@ -73,6 +223,39 @@ const char* importMediaFile(
     const char*                 media_file_path
 );
 
+//---------------------------------------------------------
+// Probe a Media File without importing it:
+//---------------------------------------------------------
+const char* probeMediaFile(
+    const char*                 media_file_path
+);
+
+//---------------------------------------------------------
+// Merge Chaptered Media Files into one Gyroflow Project:
+//---------------------------------------------------------
+const char* importChapteredMediaFiles(
+    const char*                 chapter_paths_json
+);
+
+//---------------------------------------------------------
+// Find the Sidecar Files next to a Media File:
+//---------------------------------------------------------
+const char* findMediaSidecars(
+    const char*                 media_file_path
+);
+
+//---------------------------------------------------------
+// Import a standalone Gyro Log with the video metadata:
+//---------------------------------------------------------
+const char* importGyroLogFile(
+    const char*                 gyro_log_path,
+    uint32_t                    width,
+    uint32_t                    height,
+    double                      fps,
+    double                      duration,
+    double                      start_offset
+);
+
 //---------------------------------------------------------
 // Does the Gyroflow Project contain Stabilisation Data?
 //---------------------------------------------------------
@@ -102,6 +285,69 @@ const char* loadLensProfile(
     const char*                 lens_profile_path
 );
 
+//---------------------------------------------------------
+// Validate a Lens Profile (path or JSON):
+//---------------------------------------------------------
+const char* validateLensProfile(
+    const char*                 lens_profile
+);
+
+//---------------------------------------------------------
+// Convert an Adobe Lens Correction Profile (.lcp):
+//---------------------------------------------------------
+const char* importAdobeLensProfile(
+    const char*                 lcp_path,
+    const char*                 output_directory,
+    uint32_t                    fallback_width,
+    uint32_t                    fallback_height,
+    uint8_t                     register_in_library
+);
+
+//---------------------------------------------------------
+// Lens Calibration from chessboard frames (only available
+// when the Rust library is built with the `calibration`
+// feature):
+//---------------------------------------------------------
+const char* startLensCalibration(
+    const char*                 calibration_identifier,
+    uint32_t                    board_columns,
+    uint32_t                    board_rows,
+    uint32_t                    width,
+    uint32_t                    height
+);
+
+int addLensCalibrationFrame(
+    const char*                 calibration_identifier,
+    int64_t                     timestamp,
+    const uint8_t*              pixels,
+    uint32_t                    bytes_per_row,
+    uint32_t                    height,
+    uint32_t                    channels
+);
+
+const char* finishLensCalibration(
+    const char*                 calibration_identifier,
+    const char*                 output_path,
+    const char*                 camera_brand,
+    const char*                 camera_model,
+    const char*                 lens_model,
+    const char*                 camera_setting,
+    double                      fps
+);
+
+void cancelLensCalibration(
+    const char*                 calibration_identifier
+);
+
+//---------------------------------------------------------
+// Suggest (and optionally apply) a Lens Profile:
+//---------------------------------------------------------
+const char* suggestLensProfile(
+    const char*                 gyroflow_project_data,
+    uint32_t                    max_results,
+    double                      auto_apply_threshold
+);
+
 //---------------------------------------------------------
 // Load a Preset into a Gyroflow Project:
 //---------------------------------------------------------
@@ -110,6 +356,35 @@ const char* loadPreset(
     const char*                 lens_profile_path
 );
 
+//---------------------------------------------------------
+// Set the Additional Rotation & Translation of a Gyroflow
+// Project:
+//---------------------------------------------------------
+const char* setAdditionalRotationAndTranslation(
+    const char*                 gyroflow_project_data,
+    double                      additional_rotation_pitch,
+    double                      additional_rotation_yaw,
+    double                      additional_rotation_roll,
+    double                      additional_translation_x,
+    double                      additional_translation_y,
+    double                      additional_translation_z
+);
+
+//---------------------------------------------------------
+// Set the Keyframes for an instance:
+//---------------------------------------------------------
+const char* setKeyframes(
+    const char*                 unique_identifier,
+    const char*                 keyframes_json
+);
+
+//---------------------------------------------------------
+// Clear the Keyframes for an instance:
+//---------------------------------------------------------
+void clearKeyframes(
+    const char*                 unique_identifier
+);
+
 //---------------------------------------------------------
 // Gets the Lens Identifier:
 //---------------------------------------------------------
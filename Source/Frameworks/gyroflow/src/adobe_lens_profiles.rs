@@ -0,0 +1,650 @@
+//! # Gyroflow Toolbox: Adobe Lens Profile Import
+//!
+//! This module converts Adobe Lens Correction Profiles (`.lcp`) into `gyroflow_core` lens profile JSON.
+//!
+//! An LCP file is an XMP document containing a list of camera profiles, one for each combination of focal length,
+//! focus distance & aperture that was measured. Each one can have a geometric distortion model (`PerspectiveModel`
+//! or `FisheyeModel`), along with vignette & chromatic aberration models that Gyroflow can't represent.
+//!
+//! Gyroflow lens profiles are a single, fixed calibration, so one lens profile is written for each focal length,
+//! using the measurement taken furthest from the lens. Anything that doesn't convert exactly is reported back.
+
+//---------------------------------------------------------
+// Local name bindings:
+//---------------------------------------------------------
+use crate::lens_validation;
+
+use roxmltree::{ Document, Node };          // A read-only XML tree, used to parse the LCP files
+use serde::Serialize;                       // Allows us to serialize the conversion report
+use std::path::Path;                        // Allows us to use `Path`
+
+//---------------------------------------------------------
+// The long side of a 35mm full frame sensor in millimetres,
+// used when an LCP profile only has a focal length in
+// millimetres:
+//---------------------------------------------------------
+const FULL_FRAME_LONG_SIDE_MM: f64 = 36.0;
+
+/// A lens profile converted from an LCP file.
+#[derive(Serialize)]
+pub(crate) struct ConvertedLensProfile {
+    pub identifier: String,
+    pub path: String,
+    pub focal_length: Option<f64>,
+    pub distortion_model: String,
+    pub exact: bool,
+    pub warnings: Vec<String>,
+}
+
+/// The result of converting an LCP file.
+#[derive(Default, Serialize)]
+pub(crate) struct AdobeLensProfileReport {
+    pub profiles: Vec<ConvertedLensProfile>,
+    pub skipped: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// A single camera profile from an LCP file.
+struct CameraProfile {
+    make: String,
+    model: String,
+    lens: String,
+    focal_length: Option<f64>,
+    focus_distance: Option<f64>,
+    aperture: Option<f64>,
+    sensor_format_factor: Option<f64>,
+    width: Option<usize>,
+    height: Option<usize>,
+    raw: bool,
+    geometry: Option<GeometricModel>,
+    has_vignette: bool,
+    has_chromatic_aberration: bool,
+}
+
+/// The geometric distortion model of an LCP camera profile.
+struct GeometricModel {
+    fisheye: bool,
+    focal_length_x: Option<f64>,
+    focal_length_y: Option<f64>,
+    center_x: f64,
+    center_y: f64,
+    radial: [f64; 3],
+    tangential: [f64; 2],
+    scale_factor: f64,
+    residual_mean_error: Option<f64>,
+}
+
+/// Converts an LCP file into Gyroflow lens profiles.
+///
+/// # Arguments
+///
+/// * `lcp_path` - The path to the LCP file.
+/// * `output_directory` - The directory to write the lens profile JSON files to.
+/// * `fallback_width` - The image width to use if the LCP profile doesn't specify one.
+/// * `fallback_height` - The image height to use if the LCP profile doesn't specify one.
+///
+/// # Returns
+///
+/// The lens profiles that were written (each with the parts that couldn't be converted exactly), the camera
+/// profiles that were skipped, and any errors.
+pub(crate) fn convert_lcp_file(lcp_path: &Path, output_directory: &Path, fallback_width: usize, fallback_height: usize) -> AdobeLensProfileReport {
+    let mut report = AdobeLensProfileReport::default();
+
+    let xml = match std::fs::read_to_string(lcp_path) {
+        Ok(xml) => xml,
+        Err(e) => {
+            report.errors.push(format!("The LCP file couldn't be read: {}", e));
+            return report;
+        }
+    };
+
+    let source_name = lcp_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let converted_profiles = convert_lcp(&xml, &source_name, fallback_width, fallback_height, &mut report);
+
+    if !converted_profiles.is_empty() {
+        if let Err(e) = std::fs::create_dir_all(output_directory) {
+            report.errors.push(format!("The output directory couldn't be created: {}", e));
+            return report;
+        }
+    }
+
+    for (json, mut converted) in converted_profiles {
+        let path = output_directory.join(format!("{}.json", converted.identifier));
+        if let Err(e) = std::fs::write(&path, json) {
+            report.errors.push(format!("The lens profile couldn't be written to {:?}: {}", path, e));
+            continue;
+        }
+        converted.path = path.to_string_lossy().to_string();
+        report.profiles.push(converted);
+    }
+
+    log::info!("[Gyroflow Toolbox Rust] Converted {} Lens Profile(s) from {:?}.", report.profiles.len(), lcp_path);
+    for error in &report.errors {
+        log::error!("[Gyroflow Toolbox Rust] LCP conversion error: {}", error);
+    }
+
+    report
+}
+
+/// Converts the contents of an LCP file into validated Gyroflow lens profile JSON, without writing anything.
+///
+/// # Arguments
+///
+/// * `xml` - The contents of the LCP file.
+/// * `source_name` - The name of the LCP file, which is noted in the lens profiles.
+/// * `fallback_width` - The image width to use if the LCP profile doesn't specify one.
+/// * `fallback_height` - The image height to use if the LCP profile doesn't specify one.
+/// * `report` - Where the skipped camera profiles & errors are added.
+///
+/// # Returns
+///
+/// The JSON & conversion details of each lens profile.
+fn convert_lcp(xml: &str, source_name: &str, fallback_width: usize, fallback_height: usize, report: &mut AdobeLensProfileReport) -> Vec<(String, ConvertedLensProfile)> {
+    let document = match Document::parse(xml) {
+        Ok(document) => document,
+        Err(e) => {
+            report.errors.push(format!("The LCP file couldn't be parsed: {}", e));
+            return Vec::new();
+        }
+    };
+
+    let camera_profiles = parse_camera_profiles(&document);
+    if camera_profiles.is_empty() {
+        report.errors.push("The LCP file doesn't contain any camera profiles.".to_string());
+        return Vec::new();
+    }
+
+    //---------------------------------------------------------
+    // Pick one camera profile for each focal length (& raw or
+    // JPEG), preferring the furthest focus distance:
+    //---------------------------------------------------------
+    let mut selected: Vec<&CameraProfile> = Vec::new();
+    for profile in &camera_profiles {
+        if profile.geometry.is_none() {
+            report.skipped.push(format!("{} has no geometric distortion model.", describe(profile)));
+            continue;
+        }
+        match selected.iter_mut().find(|existing| existing.focal_length == profile.focal_length && existing.raw == profile.raw) {
+            Some(existing) => {
+                if profile.focus_distance.unwrap_or(f64::MAX) > existing.focus_distance.unwrap_or(f64::MAX) {
+                    report.skipped.push(format!("{} was replaced by a measurement at a further focus distance.", describe(existing)));
+                    *existing = profile;
+                } else {
+                    report.skipped.push(format!("{} was skipped, as Gyroflow lens profiles don't vary with focus distance or aperture.", describe(profile)));
+                }
+            },
+            None => selected.push(profile),
+        }
+    }
+
+    let mut converted_profiles = Vec::new();
+    for profile in selected {
+        match convert_camera_profile(profile, source_name, fallback_width, fallback_height) {
+            Ok((json, converted)) => {
+                let validation = lens_validation::validate_lens_profile(&json);
+                if !validation.valid {
+                    report.errors.push(format!("{} converted to an invalid lens profile: {}", describe(profile), validation.errors.join(" ")));
+                    continue;
+                }
+                converted_profiles.push((json, converted));
+            },
+            Err(e) => report.errors.push(format!("{}: {}", describe(profile), e)),
+        }
+    }
+    converted_profiles
+}
+
+/// Converts a single LCP camera profile into Gyroflow lens profile JSON.
+///
+/// # Returns
+///
+/// The lens profile JSON, and the conversion details.
+fn convert_camera_profile(profile: &CameraProfile, source_name: &str, fallback_width: usize, fallback_height: usize) -> Result<(String, ConvertedLensProfile), String> {
+    let geometry = profile.geometry.as_ref().ok_or("There's no geometric distortion model.")?;
+    let mut warnings = Vec::new();
+
+    //---------------------------------------------------------
+    // Image Size:
+    //---------------------------------------------------------
+    let (width, height) = match (profile.width, profile.height) {
+        (Some(width), Some(height)) if width > 0 && height > 0 => (width, height),
+        _ if fallback_width > 0 && fallback_height > 0 => {
+            warnings.push(format!("The LCP profile has no image size, so the supplied {}x{} was used.", fallback_width, fallback_height));
+            (fallback_width, fallback_height)
+        },
+        _ => return Err("The LCP profile has no image size, and none was supplied.".to_string()),
+    };
+
+    //---------------------------------------------------------
+    // Camera Matrix (focal lengths are normalised to the long
+    // side of the image, and the centre to each side):
+    //---------------------------------------------------------
+    let long_side = width.max(height) as f64;
+    let (focal_length_x, focal_length_y) = match (geometry.focal_length_x, geometry.focal_length_y) {
+        (Some(x), Some(y)) => (x, y),
+        (Some(x), None) | (None, Some(x)) => (x, x),
+        (None, None) => {
+            let focal_length = profile.focal_length.ok_or("The LCP profile has no focal length.")?;
+            let crop_factor = profile.sensor_format_factor.unwrap_or(1.0);
+            warnings.push("The LCP profile has no normalised focal length, so it was estimated from the focal length and sensor size.".to_string());
+            let normalised = focal_length * crop_factor / FULL_FRAME_LONG_SIDE_MM;
+            (normalised, normalised)
+        }
+    };
+    let fx = focal_length_x * long_side;
+    let fy = focal_length_y * long_side;
+    let cx = geometry.center_x * width as f64;
+    let cy = geometry.center_y * height as f64;
+
+    //---------------------------------------------------------
+    // Distortion Model:
+    //---------------------------------------------------------
+    let [k1, k2, k3] = geometry.radial;
+    let [p1, p2] = geometry.tangential;
+    let (distortion_model, distortion_coeffs) = if geometry.fisheye {
+        if k3 != 0.0 || p1 != 0.0 || p2 != 0.0 {
+            warnings.push("The fisheye model has extra distortion parameters that Gyroflow's fisheye model doesn't use.".to_string());
+        }
+        ("opencv_fisheye", vec![k1, k2, 0.0, 0.0])
+    } else {
+        ("opencv_standard", vec![k1, k2, p1, p2, k3])
+    };
+    if (geometry.scale_factor - 1.0).abs() > f64::EPSILON {
+        warnings.push(format!("The LCP scale factor of {} isn't supported.", geometry.scale_factor));
+    }
+
+    //---------------------------------------------------------
+    // Anything else Gyroflow can't represent:
+    //---------------------------------------------------------
+    if profile.has_vignette {
+        warnings.push("Vignette correction isn't supported, so it was ignored.".to_string());
+    }
+    if profile.has_chromatic_aberration {
+        warnings.push("Chromatic aberration correction isn't supported, so it was ignored.".to_string());
+    }
+
+    let focal_length_setting = profile.focal_length.map(|focal_length| format!("{}mm", focal_length)).unwrap_or_default();
+    let camera_setting = if profile.raw { format!("{} Raw", focal_length_setting).trim().to_string() } else { focal_length_setting };
+    let identifier = profile_identifier(&[&profile.make, &profile.model, &profile.lens, &camera_setting, &format!("{}x{}", width, height)]);
+
+    let mut note = format!("Converted from the Adobe Lens Correction Profile \"{}\".", source_name);
+    if !warnings.is_empty() {
+        note.push(' ');
+        note.push_str(&warnings.join(" "));
+    }
+
+    let json = serde_json::json!({
+        "identifier":               identifier,
+        "calibrated_by":            "Adobe (converted by Gyroflow Toolbox)",
+        "note":                     note,
+        "camera_brand":             profile.make,
+        "camera_model":             profile.model,
+        "lens_model":               profile.lens,
+        "camera_setting":           camera_setting,
+        "calib_dimension":          { "w": width, "h": height },
+        "orig_dimension":           { "w": width, "h": height },
+        "input_horizontal_stretch": 1.0,
+        "input_vertical_stretch":   1.0,
+        "fps":                      0.0,
+        "official":                 false,
+        "focal_length":             profile.focal_length,
+        "crop_factor":              profile.sensor_format_factor,
+        "distortion_model":         distortion_model,
+        "fisheye_params": {
+            "RMS_error":            geometry.residual_mean_error.unwrap_or(0.0),
+            "camera_matrix":        [[fx, 0.0, cx], [0.0, fy, cy], [0.0, 0.0, 1.0]],
+            "distortion_coeffs":    distortion_coeffs,
+        },
+    });
+    let json = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
+
+    let converted = ConvertedLensProfile {
+        identifier,
+        path: String::new(),
+        focal_length: profile.focal_length,
+        distortion_model: distortion_model.to_string(),
+        exact: warnings.is_empty(),
+        warnings,
+    };
+    Ok((json, converted))
+}
+
+/// Finds all the camera profiles in an LCP document.
+fn parse_camera_profiles(document: &Document) -> Vec<CameraProfile> {
+    let Some(camera_profiles) = document.descendants().find(|node| node.is_element() && node.tag_name().name() == "CameraProfiles") else {
+        return Vec::new();
+    };
+
+    camera_profiles.descendants()
+        .filter(|node| node.is_element() && node.tag_name().name() == "li")
+        .map(|item| {
+            let description = resource(item);
+            let model_node = |name: &str| child(description, name).map(resource);
+
+            let (geometry_node, fisheye) = match model_node("FisheyeModel") {
+                Some(node) => (Some(node), true),
+                None => (model_node("PerspectiveModel"), false),
+            };
+
+            //---------------------------------------------------------
+            // Vignette & chromatic aberration models can be attached
+            // to the camera profile or the geometric model:
+            //---------------------------------------------------------
+            let has_model = |name: &str| child(description, name).is_some() || geometry_node.map(|node| child(node, name).is_some()).unwrap_or(false);
+
+            CameraProfile {
+                make: text_property(description, "Make"),
+                model: first_text_property(description, &["Model", "CameraPrettyName"]),
+                lens: first_text_property(description, &["LensPrettyName", "Lens"]),
+                focal_length: number_property(description, "FocalLength"),
+                focus_distance: number_property(description, "FocusDistance"),
+                aperture: number_property(description, "ApertureValue"),
+                sensor_format_factor: number_property(description, "SensorFormatFactor"),
+                width: number_property(description, "ImageWidth").map(|value| value as usize),
+                height: number_property(description, "ImageLength").map(|value| value as usize),
+                raw: property(description, "CameraRawProfile").map(|value| value.eq_ignore_ascii_case("true")).unwrap_or(false),
+                geometry: geometry_node.map(|node| GeometricModel {
+                    fisheye,
+                    focal_length_x: number_property(node, "FocalLengthX"),
+                    focal_length_y: number_property(node, "FocalLengthY"),
+                    center_x: number_property(node, "ImageXCenter").unwrap_or(0.5),
+                    center_y: number_property(node, "ImageYCenter").unwrap_or(0.5),
+                    radial: [
+                        number_property(node, "RadialDistortParam1").unwrap_or(0.0),
+                        number_property(node, "RadialDistortParam2").unwrap_or(0.0),
+                        number_property(node, "RadialDistortParam3").unwrap_or(0.0),
+                    ],
+                    tangential: [
+                        number_property(node, "TangentialDistortParam1").unwrap_or(0.0),
+                        number_property(node, "TangentialDistortParam2").unwrap_or(0.0),
+                    ],
+                    scale_factor: number_property(node, "ScaleFactor").unwrap_or(1.0),
+                    residual_mean_error: number_property(node, "ResidualMeanError"),
+                }),
+                has_vignette: has_model("VignetteModel"),
+                has_chromatic_aberration: has_model("ChromaticRedGreenModel") || has_model("ChromaticBlueGreenModel") || has_model("ChromaticGreenModel"),
+            }
+        })
+        .collect()
+}
+
+/// The node holding the properties of an RDF resource: either the node itself (`rdf:parseType="Resource"`), or
+/// its `rdf:Description` child.
+fn resource<'a, 'input>(node: Node<'a, 'input>) -> Node<'a, 'input> {
+    child(node, "Description").unwrap_or(node)
+}
+
+/// The first child element with the supplied local name.
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.is_element() && child.tag_name().name() == name)
+}
+
+/// An XMP property, which can either be an attribute or a child element.
+fn property<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attributes().find(|attribute| attribute.name() == name).map(|attribute| attribute.value())
+        .or_else(|| child(node, name).and_then(|child| child.text()))
+        .map(str::trim)
+}
+
+fn text_property(node: Node, name: &str) -> String {
+    property(node, name).unwrap_or_default().to_string()
+}
+
+fn first_text_property(node: Node, names: &[&str]) -> String {
+    names.iter().find_map(|name| property(node, name).filter(|value| !value.is_empty())).unwrap_or_default().to_string()
+}
+
+fn number_property(node: Node, name: &str) -> Option<f64> {
+    property(node, name).and_then(|value| value.parse::<f64>().ok()).filter(|value| value.is_finite())
+}
+
+/// A short description of a camera profile, for the report.
+fn describe(profile: &CameraProfile) -> String {
+    let mut description = format!("{} {} {}", profile.make, profile.model, profile.lens).trim().to_string();
+    if let Some(focal_length) = profile.focal_length {
+        description.push_str(&format!(" at {}mm", focal_length));
+    }
+    if let Some(focus_distance) = profile.focus_distance {
+        description.push_str(&format!(", focused at {}m", focus_distance));
+    }
+    if let Some(aperture) = profile.aperture {
+        description.push_str(&format!(", aperture value {}", aperture));
+    }
+    description
+}
+
+/// Builds a lens profile identifier from its parts.
+fn profile_identifier(parts: &[&str]) -> String {
+    parts.join(" ")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Two measurements at 24mm (attribute properties), one at 70mm without an image size (element properties), and
+    // one at 50mm with only a vignette model:
+    const SAMPLE_LCP: &str = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:photoshop="http://ns.adobe.com/photoshop/1.0/"
+    xmlns:stCamera="http://ns.adobe.com/photoshop/1.0/camera-profile">
+   <photoshop:CameraProfiles>
+    <rdf:Seq>
+     <rdf:li>
+      <rdf:Description
+        stCamera:Make="Canon"
+        stCamera:Model="Canon EOS 5D Mark III"
+        stCamera:Lens="EF24-70mm f/2.8L II USM"
+        stCamera:LensPrettyName="Canon EF 24-70mm f/2.8L II USM"
+        stCamera:CameraRawProfile="True"
+        stCamera:FocalLength="24"
+        stCamera:FocusDistance="0.38"
+        stCamera:ApertureValue="3"
+        stCamera:SensorFormatFactor="1"
+        stCamera:ImageWidth="5760"
+        stCamera:ImageLength="3840">
+       <stCamera:PerspectiveModel
+         stCamera:FocalLengthX="0.7"
+         stCamera:FocalLengthY="0.7"
+         stCamera:RadialDistortParam1="-0.1"
+         stCamera:RadialDistortParam2="0.05"
+         stCamera:RadialDistortParam3="-0.01"/>
+      </rdf:Description>
+     </rdf:li>
+     <rdf:li>
+      <rdf:Description
+        stCamera:Make="Canon"
+        stCamera:Model="Canon EOS 5D Mark III"
+        stCamera:Lens="EF24-70mm f/2.8L II USM"
+        stCamera:LensPrettyName="Canon EF 24-70mm f/2.8L II USM"
+        stCamera:CameraRawProfile="True"
+        stCamera:FocalLength="24"
+        stCamera:FocusDistance="10"
+        stCamera:ApertureValue="3"
+        stCamera:SensorFormatFactor="1"
+        stCamera:ImageWidth="5760"
+        stCamera:ImageLength="3840">
+       <stCamera:PerspectiveModel
+         stCamera:FocalLengthX="0.7"
+         stCamera:FocalLengthY="0.7"
+         stCamera:ImageXCenter="0.5"
+         stCamera:ImageYCenter="0.5"
+         stCamera:RadialDistortParam1="-0.12"
+         stCamera:RadialDistortParam2="0.05"
+         stCamera:RadialDistortParam3="-0.01"
+         stCamera:ResidualMeanError="0.002">
+        <stCamera:VignetteModel stCamera:FocalLengthX="0.7" stCamera:VignetteModelParam1="-0.5"/>
+       </stCamera:PerspectiveModel>
+      </rdf:Description>
+     </rdf:li>
+     <rdf:li rdf:parseType="Resource">
+      <stCamera:Make>Canon</stCamera:Make>
+      <stCamera:Model>Canon EOS 5D Mark III</stCamera:Model>
+      <stCamera:Lens>EF24-70mm f/2.8L II USM</stCamera:Lens>
+      <stCamera:CameraRawProfile>False</stCamera:CameraRawProfile>
+      <stCamera:FocalLength>70</stCamera:FocalLength>
+      <stCamera:PerspectiveModel rdf:parseType="Resource">
+       <stCamera:FocalLengthX>2.0</stCamera:FocalLengthX>
+       <stCamera:RadialDistortParam1>0.02</stCamera:RadialDistortParam1>
+       <stCamera:TangentialDistortParam1>0.001</stCamera:TangentialDistortParam1>
+      </stCamera:PerspectiveModel>
+     </rdf:li>
+     <rdf:li>
+      <rdf:Description
+        stCamera:Make="Canon"
+        stCamera:Model="Canon EOS 5D Mark III"
+        stCamera:Lens="EF24-70mm f/2.8L II USM"
+        stCamera:FocalLength="50">
+       <stCamera:VignetteModel stCamera:FocalLengthX="1.4" stCamera:VignetteModelParam1="-0.3"/>
+      </rdf:Description>
+     </rdf:li>
+    </rdf:Seq>
+   </photoshop:CameraProfiles>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>"#;
+
+    const SAMPLE_FISHEYE_LCP: &str = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:photoshop="http://ns.adobe.com/photoshop/1.0/"
+    xmlns:stCamera="http://ns.adobe.com/photoshop/1.0/camera-profile">
+   <photoshop:CameraProfiles>
+    <rdf:Seq>
+     <rdf:li>
+      <rdf:Description
+        stCamera:Make="GoPro"
+        stCamera:Model="HERO9 Black"
+        stCamera:Lens="Wide"
+        stCamera:FocalLength="3"
+        stCamera:ImageWidth="4000"
+        stCamera:ImageLength="3000">
+       <stCamera:FisheyeModel
+         stCamera:FocalLengthX="0.45"
+         stCamera:RadialDistortParam1="0.03"
+         stCamera:RadialDistortParam2="-0.01"/>
+      </rdf:Description>
+     </rdf:li>
+    </rdf:Seq>
+   </photoshop:CameraProfiles>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>"#;
+
+    fn parse_json(json: &str) -> serde_json::Value {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn parses_attribute_and_element_properties() {
+        let document = Document::parse(SAMPLE_LCP).unwrap();
+        let profiles = parse_camera_profiles(&document);
+        assert_eq!(profiles.len(), 4);
+
+        let first = &profiles[0];
+        assert_eq!(first.make, "Canon");
+        assert_eq!(first.model, "Canon EOS 5D Mark III");
+        assert_eq!(first.lens, "Canon EF 24-70mm f/2.8L II USM");
+        assert_eq!(first.focal_length, Some(24.0));
+        assert_eq!(first.focus_distance, Some(0.38));
+        assert_eq!((first.width, first.height), (Some(5760), Some(3840)));
+        assert!(first.raw);
+        let geometry = first.geometry.as_ref().unwrap();
+        assert!(!geometry.fisheye);
+        assert_eq!(geometry.radial, [-0.1, 0.05, -0.01]);
+        assert_eq!((geometry.center_x, geometry.center_y), (0.5, 0.5));
+        assert!(!first.has_vignette);
+
+        assert!(profiles[1].has_vignette);
+
+        let element_profile = &profiles[2];
+        assert_eq!(element_profile.lens, "EF24-70mm f/2.8L II USM");
+        assert_eq!(element_profile.focal_length, Some(70.0));
+        assert_eq!((element_profile.width, element_profile.height), (None, None));
+        assert!(!element_profile.raw);
+        let geometry = element_profile.geometry.as_ref().unwrap();
+        assert_eq!(geometry.focal_length_x, Some(2.0));
+        assert_eq!(geometry.tangential, [0.001, 0.0]);
+
+        assert!(profiles[3].geometry.is_none());
+        assert!(profiles[3].has_vignette);
+    }
+
+    #[test]
+    fn converts_one_profile_per_focal_length_at_the_furthest_focus_distance() {
+        let mut report = AdobeLensProfileReport::default();
+        let converted = convert_lcp(SAMPLE_LCP, "sample.lcp", 5760, 3840, &mut report);
+        assert!(report.errors.is_empty(), "errors: {:?}", report.errors);
+        assert_eq!(converted.len(), 2);
+        assert!(report.skipped.iter().any(|skipped| skipped.contains("replaced by a measurement at a further focus distance")));
+        assert!(report.skipped.iter().any(|skipped| skipped.contains("has no geometric distortion model")));
+
+        let (json, details) = &converted[0];
+        assert!(details.identifier.contains("24mm_Raw_5760x3840"));
+        assert_eq!(details.distortion_model, "opencv_standard");
+        assert!(!details.exact);
+        assert!(details.warnings.iter().any(|warning| warning.contains("Vignette")));
+
+        let json = parse_json(json);
+        assert_eq!(json["camera_setting"], "24mm Raw");
+        assert_eq!(json["fisheye_params"]["camera_matrix"][0][0].as_f64(), Some(0.7 * 5760.0));
+        assert_eq!(json["fisheye_params"]["camera_matrix"][1][2].as_f64(), Some(1920.0));
+        let coefficients: Vec<f64> = serde_json::from_value(json["fisheye_params"]["distortion_coeffs"].clone()).unwrap();
+        assert_eq!(coefficients, vec![-0.12, 0.05, 0.0, 0.0, -0.01]);
+    }
+
+    #[test]
+    fn uses_the_fallback_image_size() {
+        let mut report = AdobeLensProfileReport::default();
+        let converted = convert_lcp(SAMPLE_LCP, "sample.lcp", 5760, 3840, &mut report);
+        let (json, details) = &converted[1];
+        assert!(details.warnings.iter().any(|warning| warning.contains("5760x3840 was used")));
+
+        let json = parse_json(json);
+        assert_eq!(json["calib_dimension"]["w"], 5760);
+        assert_eq!(json["fisheye_params"]["camera_matrix"][0][0].as_f64(), Some(2.0 * 5760.0));
+        assert_eq!(json["fisheye_params"]["camera_matrix"][1][1].as_f64(), Some(2.0 * 5760.0));
+    }
+
+    #[test]
+    fn a_missing_image_size_without_a_fallback_is_an_error() {
+        let mut report = AdobeLensProfileReport::default();
+        let converted = convert_lcp(SAMPLE_LCP, "sample.lcp", 0, 0, &mut report);
+        assert_eq!(converted.len(), 1);
+        assert!(report.errors.iter().any(|error| error.contains("no image size")));
+    }
+
+    #[test]
+    fn converts_the_fisheye_model() {
+        let mut report = AdobeLensProfileReport::default();
+        let converted = convert_lcp(SAMPLE_FISHEYE_LCP, "fisheye.lcp", 0, 0, &mut report);
+        assert!(report.errors.is_empty(), "errors: {:?}", report.errors);
+        assert_eq!(converted.len(), 1);
+
+        let (json, details) = &converted[0];
+        assert_eq!(details.distortion_model, "opencv_fisheye");
+        assert!(details.exact, "warnings: {:?}", details.warnings);
+
+        let json = parse_json(json);
+        let coefficients: Vec<f64> = serde_json::from_value(json["fisheye_params"]["distortion_coeffs"].clone()).unwrap();
+        assert_eq!(coefficients, vec![0.03, -0.01, 0.0, 0.0]);
+        assert_eq!(json["fisheye_params"]["camera_matrix"][0][0].as_f64(), Some(0.45 * 4000.0));
+        assert_eq!(json["fisheye_params"]["camera_matrix"][0][2].as_f64(), Some(2000.0));
+    }
+
+    #[test]
+    fn invalid_xml_and_empty_documents_are_errors() {
+        let mut report = AdobeLensProfileReport::default();
+        assert!(convert_lcp("<x:xmpmeta", "broken.lcp", 0, 0, &mut report).is_empty());
+        assert!(report.errors[0].contains("couldn't be parsed"));
+
+        let mut report = AdobeLensProfileReport::default();
+        assert!(convert_lcp("<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"/>", "empty.lcp", 0, 0, &mut report).is_empty());
+        assert!(report.errors[0].contains("doesn't contain any camera profiles"));
+    }
+}
@@ -0,0 +1,710 @@
+//! # Gyroflow Toolbox: Manager Cache
+//!
+//! This module caches the `StabilizationManager` for each Gyroflow Toolbox instance, so that we don't have to
+//! import the Gyroflow Project and recompute the smoothing for every frame. The imported gyro data & smoothing are
+//! shared between all the render sizes of an instance.
+
+//---------------------------------------------------------
+// Local name bindings:
+//---------------------------------------------------------
+use gyroflow_core::StabilizationManager;
+use gyroflow_core::gyro_source::GyroSource;
+use gyroflow_core::stabilization_params::ReadoutDirection;
+
+use once_cell::sync::OnceCell;              // Provides two new cell-like types, unsync::OnceCell and sync::OnceCell
+use lazy_static::*;                         // A macro for declaring lazily evaluated statics
+use lru::LruCache;                          // A LRU cache implementation
+use parking_lot::RwLock;                    // The reader-writer lock used by `gyroflow_core`
+use serde::Serialize;                       // Allows us to serialize the cache statistics
+use std::num::NonZeroUsize;                 // An integer that is known not to equal zero
+use std::collections::HashMap;              // Allows us to use `HashMap`
+use std::sync::Arc;                         // Adds Atomic Reference Count support
+use std::sync::atomic::{ AtomicU64, Ordering };
+use std::sync::Mutex;                       // A mutual exclusion primitive useful for protecting shared data
+use xxhash_rust::xxh3::xxh3_64;             // A fast non-cryptographic hash
+
+// This code block defines a lazy static variable called `MANAGER_CACHE` that is a `Mutex`-protected LRU cache of `StabilizationManager` instances.
+//
+// The `lazy_static!` macro is used to ensure that the variable is initialized only once, and only when it is first accessed.
+//
+// The `Mutex` is used to ensure that the cache can be safely accessed from multiple threads.
+//
+// The `LruCache` is used to limit the size of the cache to `DEFAULT_CACHE_CAPACITY` items, which can be changed
+// at runtime with `set_capacity`.
+//
+// # Example
+//
+// ```rust
+// use crate::cache::MANAGER_CACHE;
+//
+// let cache = MANAGER_CACHE.lock().unwrap();
+// let entry = cache.get(&cache_key).unwrap();
+// ```
+lazy_static! {
+    pub(crate) static ref MANAGER_CACHE: Mutex<LruCache<CacheKey, CacheEntry>> = Mutex::new(LruCache::new(NonZeroUsize::new(DEFAULT_CACHE_CAPACITY).unwrap()));
+}
+
+//---------------------------------------------------------
+// The default number of entries in the `MANAGER_CACHE`:
+//---------------------------------------------------------
+const DEFAULT_CACHE_CAPACITY: usize = 8;
+
+//---------------------------------------------------------
+// The maximum number of render sizes kept for each entry
+// (i.e. the viewer, a thumbnail, a filmstrip & an export):
+//---------------------------------------------------------
+const MAXIMUM_RENDERS_PER_MANAGER: usize = 4;
+
+//---------------------------------------------------------
+// The number of bytes at the start & end of the Gyroflow
+// Project data that are checked before a memoised project
+// hash is reused:
+//---------------------------------------------------------
+const PROJECT_HASH_EDGE_SIZE: usize = 4096;
+
+//---------------------------------------------------------
+// Rough sizes used to estimate the memory used by a
+// `StabilizationManager`, in bytes:
+//---------------------------------------------------------
+const ESTIMATED_IMU_SAMPLE_SIZE: usize = 104;      // A timestamped raw gyro, accelerometer & magnetometer sample
+const ESTIMATED_QUATERNION_SIZE: usize = 64;       // A timestamped quaternion, including the `BTreeMap` overhead
+
+// The identifier of the next `CachedManager`.
+static NEXT_MANAGER_ID: AtomicU64 = AtomicU64::new(1);
+
+// The last project hash for each unique identifier, so that unchanged Gyroflow Project data isn't hashed again on
+// every frame.
+lazy_static! {
+    static ref PROJECT_HASHES: Mutex<HashMap<String, ProjectHashMemo>> = Mutex::new(HashMap::new());
+}
+
+// The memory budget of the `MANAGER_CACHE` in bytes (0 means no budget).
+static MEMORY_BUDGET: AtomicU64 = AtomicU64::new(0);
+
+// The `MANAGER_CACHE` statistics, for support diagnostics.
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+static CACHE_EVICTIONS: AtomicU64 = AtomicU64::new(0);
+
+/// An entry in the `MANAGER_CACHE`.
+///
+/// The cache lock is only held to find or insert an entry. The (potentially slow) Gyroflow Project import happens
+/// afterwards via `OnceCell::get_or_init`, so any other render threads asking for the same key wait for that
+/// first import instead of importing the same Gyroflow Project again.
+pub(crate) type CacheEntry = Arc<OnceCell<Arc<CachedManager>>>;
+
+/// What a `MANAGER_CACHE` entry is used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub(crate) enum CacheKind {
+    Stabilisation,
+    LensCorrection,
+    Redistort,
+}
+
+/// The key of a `MANAGER_CACHE` entry.
+///
+/// The key includes a hash of the Gyroflow Project data, so if a project is edited (keeping the same path & unique
+/// identifier), the changed data automatically produces a fresh import.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub(crate) struct CacheKey {
+    pub kind: CacheKind,
+    pub unique_identifier: String,
+    pub project_path: String,
+    pub project_hash: Option<u64>,
+    pub lens_profile: String,
+    pub render_size: Option<(usize, usize, String)>,
+    pub disable_gyroflow_stretch: bool,
+}
+
+impl CacheKey {
+    /// The key for stabilising a Gyroflow Project. The render size isn't part of the key, as the gyro data &
+    /// smoothing are shared between all render sizes.
+    pub(crate) fn stabilisation(unique_identifier: &str, project_path: &str, project_data: &[u8], disable_gyroflow_stretch: bool) -> Self {
+        Self {
+            kind: CacheKind::Stabilisation,
+            unique_identifier: unique_identifier.to_string(),
+            project_path: project_path.to_string(),
+            project_hash: Some(memoised_project_hash(unique_identifier, project_data)),
+            lens_profile: String::new(),
+            render_size: None,
+            disable_gyroflow_stretch,
+        }
+    }
+
+    /// The key for correcting the lens distortion of a clip with just a lens profile.
+    pub(crate) fn lens_correction(unique_identifier: &str, lens_profile: &str, width: usize, height: usize, pixel_format: &str, disable_gyroflow_stretch: bool) -> Self {
+        Self {
+            kind: CacheKind::LensCorrection,
+            unique_identifier: unique_identifier.to_string(),
+            project_path: String::new(),
+            project_hash: None,
+            lens_profile: lens_profile.to_string(),
+            render_size: Some((width, height, pixel_format.to_string())),
+            disable_gyroflow_stretch,
+        }
+    }
+
+    /// The key for re-distorting graphics to match a Gyroflow Project.
+    pub(crate) fn redistort(unique_identifier: &str, project_path: &str, project_data: &[u8]) -> Self {
+        Self {
+            kind: CacheKind::Redistort,
+            unique_identifier: unique_identifier.to_string(),
+            project_path: project_path.to_string(),
+            project_hash: Some(memoised_project_hash(unique_identifier, project_data)),
+            lens_profile: String::new(),
+            render_size: None,
+            disable_gyroflow_stretch: false,
+        }
+    }
+
+    /// Whether or not this key is for an older version of the same Gyroflow Project as `other`.
+    fn is_superseded_by(&self, other: &CacheKey) -> bool {
+        self.kind == other.kind
+            && self.unique_identifier == other.unique_identifier
+            && self.project_path == other.project_path
+            && self.project_hash != other.project_hash
+    }
+}
+
+/// Returns a fast hash of the Gyroflow Project data.
+///
+/// This runs for every frame, and projects with embedded gyro data can be tens of megabytes, so we use XXH3
+/// rather than the (much slower) SipHash used by `DefaultHasher`.
+pub(crate) fn project_hash(project_data: &[u8]) -> u64 {
+    xxh3_64(project_data)
+}
+
+/// A memoised project hash, along with what it was computed from.
+struct ProjectHashMemo {
+    address: usize,
+    length: usize,
+    edges_hash: u64,
+    hash: u64,
+}
+
+/// Returns the hash of the first & last `PROJECT_HASH_EDGE_SIZE` bytes of the Gyroflow Project data.
+fn project_edges_hash(project_data: &[u8]) -> u64 {
+    let edge_size = PROJECT_HASH_EDGE_SIZE.min(project_data.len());
+    xxh3_64(&project_data[..edge_size]) ^ xxh3_64(&project_data[project_data.len() - edge_size..]).rotate_left(1)
+}
+
+/// Returns the hash of the Gyroflow Project data for a unique identifier, reusing the last hash if the host has
+/// passed the same buffer (the same address, length, start & end) as last time, rather than hashing what can be
+/// megabytes of gyro data on every frame.
+///
+/// The memoised hash is forgotten when the cache entries of the unique identifier are trashed, which the host
+/// does whenever it changes the Gyroflow Project data of an existing instance.
+pub(crate) fn memoised_project_hash(unique_identifier: &str, project_data: &[u8]) -> u64 {
+    let address = project_data.as_ptr() as usize;
+    let edges_hash = project_edges_hash(project_data);
+    if let Some(memo) = PROJECT_HASHES.lock().unwrap().get(unique_identifier) {
+        if memo.address == address && memo.length == project_data.len() && memo.edges_hash == edges_hash {
+            return memo.hash;
+        }
+    }
+
+    let hash = project_hash(project_data);
+    PROJECT_HASHES.lock().unwrap().insert(unique_identifier.to_string(), ProjectHashMemo {
+        address,
+        length: project_data.len(),
+        edges_hash,
+        hash,
+    });
+    hash
+}
+
+/// Forgets the memoised project hash of a unique identifier, so that its Gyroflow Project data is hashed again.
+pub(crate) fn forget_project_hash(unique_identifier: &str) {
+    PROJECT_HASHES.lock().unwrap().remove(unique_identifier);
+}
+
+/// Finds the `MANAGER_CACHE` entry for a cache key, inserting an empty entry if there isn't one yet.
+///
+/// When a new entry is inserted, any entries for older versions of the same Gyroflow Project are removed, as
+/// they'll never be used again.
+pub(crate) fn cache_entry(cache_key: &CacheKey) -> CacheEntry {
+    let mut cache = MANAGER_CACHE.lock().unwrap();
+    if let Some(entry) = cache.get(cache_key) {
+        CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+        return entry.clone();
+    }
+
+    CACHE_MISSES.fetch_add(1, Ordering::Relaxed);
+    let superseded_keys: Vec<CacheKey> = cache.iter()
+        .filter(|(key, _)| key.is_superseded_by(cache_key))
+        .map(|(key, _)| key.clone())
+        .collect();
+    for key in superseded_keys {
+        log::info!("[Gyroflow Toolbox Rust] The Gyroflow Project data has changed, so removing {:?} from the cache.", key);
+        cache.pop(&key);
+    }
+
+    let entry: CacheEntry = Arc::new(OnceCell::new());
+    if cache.push(cache_key.clone(), entry.clone()).is_some() {
+        CACHE_EVICTIONS.fetch_add(1, Ordering::Relaxed);
+    }
+    entry
+}
+
+/// Changes the maximum number of entries in the `MANAGER_CACHE`, evicting the least recently used entries if needed.
+///
+/// # Returns
+///
+/// The number of entries in the cache.
+pub(crate) fn set_capacity(capacity: usize) -> usize {
+    let mut cache = MANAGER_CACHE.lock().unwrap();
+    let previous_len = cache.len();
+    cache.resize(NonZeroUsize::new(capacity.max(1)).unwrap());
+    CACHE_EVICTIONS.fetch_add((previous_len - cache.len()) as u64, Ordering::Relaxed);
+    cache.len()
+}
+
+/// Changes the memory budget of the `MANAGER_CACHE` (0 means no budget), evicting the least recently used entries
+/// if needed.
+///
+/// # Returns
+///
+/// The number of entries in the cache.
+pub(crate) fn set_memory_budget(bytes: u64) -> usize {
+    MEMORY_BUDGET.store(bytes, Ordering::SeqCst);
+    enforce_memory_budget(None)
+}
+
+/// Removes the `MANAGER_CACHE` entries that match the supplied filter, along with the memoised project hashes of
+/// their unique identifiers. The keyframe curves supplied by the host aren't cached data, so they're kept.
+///
+/// # Returns
+///
+/// The number of entries in the cache.
+pub(crate) fn trash(filter: impl Fn(&CacheKey) -> bool) -> usize {
+    let mut cache = MANAGER_CACHE.lock().unwrap();
+    let keys: Vec<CacheKey> = cache.iter()
+        .filter(|(key, _)| filter(key))
+        .map(|(key, _)| key.clone())
+        .collect();
+    for key in keys {
+        cache.pop(&key);
+        forget_project_hash(&key.unique_identifier);
+    }
+    cache.len()
+}
+
+/// Evicts the least recently used entries until the estimated memory used by the `MANAGER_CACHE` is within the
+/// memory budget.
+///
+/// The entry for `keep` (the entry that's just been loaded) is always kept, even if it's over the budget on its
+/// own. Without a `keep` key, the most recently used entry is kept.
+///
+/// # Returns
+///
+/// The number of entries in the cache.
+pub(crate) fn enforce_memory_budget(keep: Option<&CacheKey>) -> usize {
+    let mut cache = MANAGER_CACHE.lock().unwrap();
+    let budget = MEMORY_BUDGET.load(Ordering::SeqCst);
+    if budget == 0 {
+        return cache.len();
+    }
+
+    //---------------------------------------------------------
+    // Only the most recently used entry is never evicted, so
+    // make sure that's the entry we're keeping:
+    //---------------------------------------------------------
+    if let Some(keep) = keep {
+        cache.promote(keep);
+    }
+
+    let mut estimated_memory: u64 = cache.iter().map(|(_, entry)| estimated_entry_size(entry)).sum();
+    while estimated_memory > budget && cache.len() > 1 {
+        if let Some((evicted_key, evicted_entry)) = cache.pop_lru() {
+            log::info!("[Gyroflow Toolbox Rust] Evicting {:?} from the cache to stay within the memory budget.", evicted_key);
+            estimated_memory = estimated_memory.saturating_sub(estimated_entry_size(&evicted_entry));
+            CACHE_EVICTIONS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    cache.len()
+}
+
+/// The estimated memory used by a `MANAGER_CACHE` entry, in bytes.
+fn estimated_entry_size(entry: &CacheEntry) -> u64 {
+    entry.get().map(|cached_manager| cached_manager.estimated_size()).unwrap_or(0)
+}
+
+/// A single entry in the `CacheStatistics`.
+#[derive(Serialize)]
+struct CacheEntryStatistics {
+    key: CacheKey,
+    loaded: bool,
+    render_sizes: usize,
+    estimated_size: u64,
+}
+
+/// The current state of the `MANAGER_CACHE`, for support diagnostics.
+#[derive(Serialize)]
+struct CacheStatistics {
+    capacity: usize,
+    memory_budget: u64,
+    estimated_memory: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+    entries: Vec<CacheEntryStatistics>,
+}
+
+/// Returns the current entries & statistics of the `MANAGER_CACHE` as JSON. Entries are listed from the most to
+/// the least recently used.
+pub(crate) fn statistics_json() -> String {
+    let cache = MANAGER_CACHE.lock().unwrap();
+    let entries: Vec<CacheEntryStatistics> = cache.iter().map(|(key, entry)| {
+        CacheEntryStatistics {
+            key: key.clone(),
+            loaded: entry.get().is_some(),
+            render_sizes: entry.get().map(|cached_manager| cached_manager.renders.lock().unwrap().len()).unwrap_or(0),
+            estimated_size: estimated_entry_size(entry),
+        }
+    }).collect();
+
+    let statistics = CacheStatistics {
+        capacity: cache.cap().get(),
+        memory_budget: MEMORY_BUDGET.load(Ordering::SeqCst),
+        estimated_memory: entries.iter().map(|entry| entry.estimated_size).sum(),
+        hits: CACHE_HITS.load(Ordering::Relaxed),
+        misses: CACHE_MISSES.load(Ordering::Relaxed),
+        evictions: CACHE_EVICTIONS.load(Ordering::Relaxed),
+        entries,
+    };
+    serde_json::to_string(&statistics).unwrap_or_default()
+}
+
+/// Returns the estimated memory used by the gyro data, in bytes (the raw IMU samples, and the original & smoothed
+/// orientations).
+fn estimated_gyro_size(gyro: &GyroSource) -> usize {
+    gyro.file_metadata.read().raw_imu.len() * ESTIMATED_IMU_SAMPLE_SIZE
+        + (gyro.quaternions.len() + gyro.smoothed_quaternions.len()) * ESTIMATED_QUATERNION_SIZE
+}
+
+/// Returns the estimated GPU memory used by a render, in bytes (the input & output frames).
+fn estimated_render_size((width, height, pixel_format): &RenderKey) -> usize {
+    let bytes_per_pixel = match pixel_format.as_str() {
+        "RGBAf" => 16,
+        "RGBAf16" => 8,
+        _ => 4,
+    };
+    width * height * bytes_per_pixel * 2
+}
+
+/// A `StabilizationManager` stored in the `MANAGER_CACHE`.
+///
+/// Alongside the manager itself, we keep a copy of the values that were stored in the Gyroflow Project when it
+/// was imported, so that the host can override them in `processFrame` and later go back to the project values.
+///
+/// Each `CachedManager` has its own `instance_state` lock, so that render threads for different instances don't
+/// block each other, and an `id` that's never reused, so that other caches can be keyed on it.
+///
+/// The imported gyro data and the computed smoothing don't depend on the render size, so thumbnails, filmstrips,
+/// the viewer and exports of the same Gyroflow Project all share them. Only the lightweight size-dependent render
+/// state is kept per resolution, as a `CachedRender`.
+pub(crate) struct CachedManager {
+    pub id: u64,
+    pub manager: Arc<StabilizationManager>,
+    pub project_values: ProjectValues,
+    pub applied_keyframes_revision: AtomicU64,
+    pub instance_state: Mutex<InstanceState>,
+    smoothing: Arc<SharedSmoothing>,
+    renders: Mutex<LruCache<RenderKey, Arc<CachedRender>>>,
+    estimated_size: AtomicU64,
+}
+
+impl CachedManager {
+    pub(crate) fn new(manager: StabilizationManager) -> Self {
+        let project_values = ProjectValues::from_manager(&manager);
+        let smoothing = Arc::new(SharedSmoothing::new(manager.gyro.clone()));
+        Self {
+            id: NEXT_MANAGER_ID.fetch_add(1, Ordering::Relaxed),
+            manager: Arc::new(manager),
+            project_values,
+            applied_keyframes_revision: AtomicU64::new(0),
+            instance_state: Mutex::new(InstanceState::default()),
+            smoothing,
+            renders: Mutex::new(LruCache::new(NonZeroUsize::new(MAXIMUM_RENDERS_PER_MANAGER).unwrap())),
+            estimated_size: AtomicU64::new(0),
+        }
+    }
+
+    /// The estimated memory used by the gyro data & renders, in bytes, as of the last `update_estimated_size`.
+    pub(crate) fn estimated_size(&self) -> u64 {
+        self.estimated_size.load(Ordering::SeqCst)
+    }
+
+    /// Updates the estimated memory used by the gyro data & renders. This is kept outside of the `MANAGER_CACHE`
+    /// lock, as reading the gyro data has to wait for any recompute that's in progress.
+    ///
+    /// Recomputing the smoothing creates a new copy of the gyro data, so each distinct copy that's still in use
+    /// (by the imported manager, the shared smoothing, or a render that hasn't caught up yet) is counted once.
+    pub(crate) fn update_estimated_size(&self) {
+        let renders: Vec<(RenderKey, Arc<CachedRender>)> = self.renders.lock().unwrap()
+            .iter()
+            .map(|(key, render)| (key.clone(), render.clone()))
+            .collect();
+
+        let mut gyros = vec![self.manager.gyro.clone(), self.smoothing.gyro()];
+        gyros.extend(renders.iter().map(|(_, render)| render.manager().gyro.clone()));
+        let mut gyro_size = 0;
+        for (index, gyro) in gyros.iter().enumerate() {
+            if !gyros[..index].iter().any(|other| Arc::ptr_eq(other, gyro)) {
+                gyro_size += estimated_gyro_size(&gyro.read());
+            }
+        }
+
+        let renders_size: usize = renders.iter().map(|(key, render)| render.estimated_size(key)).sum();
+        self.estimated_size.store((gyro_size + renders_size) as u64, Ordering::SeqCst);
+    }
+
+    /// Gets the `CachedRender` for a render size & pixel format, creating it if needed.
+    ///
+    /// A new render shares the gyro data, lens profile, smoothing and keyframes of the imported Gyroflow Project,
+    /// and starts with a copy of its parameters. `setup` is then called to apply the size-dependent settings.
+    ///
+    /// Only the `MAXIMUM_RENDERS_PER_MANAGER` most recently used render sizes are kept, as each one holds its own
+    /// GPU state. Render threads that are still using an evicted render keep it alive until they've finished.
+    pub(crate) fn render(&self, width: usize, height: usize, pixel_format: &str, setup: impl FnOnce(&StabilizationManager)) -> Arc<CachedRender> {
+        let mut renders = self.renders.lock().unwrap();
+        renders.get_or_insert((width, height, pixel_format.to_string()), || {
+            let mut manager = StabilizationManager::default();
+            manager.gyro = self.manager.gyro.clone();
+            manager.lens = self.manager.lens.clone();
+            manager.smoothing = self.manager.smoothing.clone();
+            manager.keyframes = self.manager.keyframes.clone();
+            manager.lens_profile_db = self.manager.lens_profile_db.clone();
+            *manager.params.write() = self.manager.params.read().clone();
+
+            setup(&manager);
+
+            let current = snapshot_manager(&manager, self.smoothing.gyro());
+            Arc::new(CachedRender {
+                requested: Arc::new(manager),
+                instance_state: Mutex::new(InstanceState::default()),
+                current: Arc::new(RwLock::new(Arc::new(current))),
+                applied_smoothing_revision: Arc::new(AtomicU64::new(0)),
+                smoothing: self.smoothing.clone(),
+                recompute_state: Arc::new(Mutex::new(RecomputeState::default())),
+            })
+        }).clone()
+    }
+}
+
+/// The render size & pixel format of a `CachedRender`.
+type RenderKey = (usize, usize, String);
+
+/// The smoothing shared by all the `CachedRender`s of a `CachedManager`.
+struct SharedSmoothing {
+    // Incremented every time the smoothing is recomputed (0 means it has never been computed).
+    revision: AtomicU64,
+    // Held while the smoothing is recomputed, so that only one render size recomputes it.
+    lock: Mutex<()>,
+    // The gyro data with the latest smoothed orientations. It's replaced (never modified) when the smoothing
+    // is recomputed, so renders can keep reading the previous one.
+    gyro: RwLock<Arc<RwLock<GyroSource>>>,
+}
+
+impl SharedSmoothing {
+    fn new(gyro: Arc<RwLock<GyroSource>>) -> Self {
+        Self {
+            revision: AtomicU64::new(0),
+            lock: Mutex::new(()),
+            gyro: RwLock::new(gyro),
+        }
+    }
+
+    /// The gyro data with the latest smoothed orientations.
+    fn gyro(&self) -> Arc<RwLock<GyroSource>> {
+        self.gyro.read().clone()
+    }
+}
+
+/// The size-dependent render state of a `CachedManager`.
+///
+/// The host's parameters are applied to the `requested` manager, which is never rendered with. Recomputes run on
+/// a snapshot of it, and the result is swapped in as the `current` manager once it's ready, so render threads
+/// never see half-applied parameters or a smoothing that's being recomputed.
+pub(crate) struct CachedRender {
+    pub requested: Arc<StabilizationManager>,
+    pub instance_state: Mutex<InstanceState>,
+    current: Arc<RwLock<Arc<StabilizationManager>>>,
+    applied_smoothing_revision: Arc<AtomicU64>,
+    smoothing: Arc<SharedSmoothing>,
+    recompute_state: Arc<Mutex<RecomputeState>>,
+}
+
+impl CachedRender {
+    /// The last fully recomputed manager, which should be used for rendering.
+    pub(crate) fn manager(&self) -> Arc<StabilizationManager> {
+        self.current.read().clone()
+    }
+
+    /// Recomputes on the calling thread. The shared smoothing is only computed if no other render size has computed
+    /// it yet, otherwise just the size-dependent zooming & undistortion are recomputed.
+    pub(crate) fn recompute_blocking(&self) {
+        let recompute_smoothing = self.smoothing.revision.load(Ordering::SeqCst) == 0;
+        let (manager, smoothing_revision) = recompute_snapshot(&self.requested, &self.smoothing, recompute_smoothing);
+        *self.current.write() = Arc::new(manager);
+        self.applied_smoothing_revision.store(smoothing_revision, Ordering::SeqCst);
+    }
+
+    /// The estimated memory used by this render, in bytes: the GPU frames, plus the per-frame zoom of both the
+    /// requested & current managers.
+    fn estimated_size(&self, key: &RenderKey) -> usize {
+        let per_frame_size = |manager: &StabilizationManager| {
+            let params = manager.params.read();
+            (params.fovs.len() + params.minimal_fovs.len()) * std::mem::size_of::<f64>()
+        };
+        estimated_render_size(key) + per_frame_size(&self.requested) + per_frame_size(&self.manager())
+    }
+
+    /// Whether or not another render size has recomputed the shared smoothing since this render last recomputed.
+    pub(crate) fn is_smoothing_outdated(&self) -> bool {
+        self.applied_smoothing_revision.load(Ordering::SeqCst) != self.smoothing.revision.load(Ordering::SeqCst)
+    }
+
+    /// Recomputes on a background thread. If `recompute_smoothing` is `false`, only the size-dependent zooming &
+    /// undistortion are recomputed, using the current shared smoothing.
+    ///
+    /// Rendering continues with the last good result until the new result is ready. If parameters change again
+    /// while a recompute is running, the requests are coalesced into a single recompute once it has finished.
+    pub(crate) fn request_recompute(&self, recompute_smoothing: bool) {
+        {
+            let mut state = self.recompute_state.lock().unwrap();
+            state.pending_render = true;
+            state.pending_smoothing |= recompute_smoothing;
+            if state.running {
+                return;
+            }
+            state.running = true;
+        }
+
+        let requested = self.requested.clone();
+        let current = self.current.clone();
+        let smoothing = self.smoothing.clone();
+        let applied_smoothing_revision = self.applied_smoothing_revision.clone();
+        let recompute_state = self.recompute_state.clone();
+        std::thread::spawn(move || {
+            loop {
+                let recompute_smoothing = {
+                    let mut state = recompute_state.lock().unwrap();
+                    if !state.pending_render {
+                        state.running = false;
+                        break;
+                    }
+                    state.pending_render = false;
+                    std::mem::take(&mut state.pending_smoothing)
+                };
+
+                if recompute_smoothing {
+                    log::info!("[Gyroflow Toolbox Rust] Recomputing the smoothing in the background...");
+                } else {
+                    log::info!("[Gyroflow Toolbox Rust] Recomputing the zooming & undistortion in the background...");
+                }
+                let (manager, smoothing_revision) = recompute_snapshot(&requested, &smoothing, recompute_smoothing);
+                *current.write() = Arc::new(manager);
+                applied_smoothing_revision.store(smoothing_revision, Ordering::SeqCst);
+            }
+        });
+    }
+}
+
+/// Recomputes a snapshot of the `requested` manager, leaving the manager that's being rendered with untouched.
+///
+/// If `recompute_smoothing` is `true`, the smoothing is recomputed on a copy of the gyro data, which then replaces
+/// the shared gyro data for all render sizes. Otherwise only the size-dependent zooming & undistortion are
+/// recomputed, using the current shared smoothing.
+///
+/// # Returns
+///
+/// The recomputed manager, and the revision of the shared smoothing it uses.
+fn recompute_snapshot(requested: &StabilizationManager, smoothing: &SharedSmoothing, recompute_smoothing: bool) -> (StabilizationManager, u64) {
+    let (manager, smoothing_revision) = if recompute_smoothing {
+        let _smoothing_lock = smoothing.lock.lock().unwrap();
+        let gyro = Arc::new(RwLock::new(smoothing.gyro().read().clone()));
+        let manager = snapshot_manager(requested, gyro);
+        manager.invalidate_smoothing();
+        manager.recompute_blocking();
+        *smoothing.gyro.write() = manager.gyro.clone();
+        (manager, smoothing.revision.fetch_add(1, Ordering::SeqCst) + 1)
+    } else {
+        // Read the revision first, so that it's never newer than the gyro data:
+        let smoothing_revision = smoothing.revision.load(Ordering::SeqCst);
+        let manager = snapshot_manager(requested, smoothing.gyro());
+        manager.recompute_adaptive_zoom();
+        manager.recompute_undistortion();
+        (manager, smoothing_revision)
+    };
+    manager.params.write().calculate_ramped_timestamps(&manager.keyframes.read(), false, false);
+    (manager, smoothing_revision)
+}
+
+/// Creates a new manager with a copy of the parameters, smoothing settings & keyframes of `requested`, and its
+/// own stabilization state, using the supplied gyro data. The lens profile is never modified after import, so
+/// it's shared.
+fn snapshot_manager(requested: &StabilizationManager, gyro: Arc<RwLock<GyroSource>>) -> StabilizationManager {
+    let mut manager = StabilizationManager::default();
+    manager.gyro = gyro;
+    manager.lens = requested.lens.clone();
+    manager.lens_profile_db = requested.lens_profile_db.clone();
+    *manager.smoothing.write() = requested.smoothing.read().clone();
+    *manager.keyframes.write() = requested.keyframes.read().clone();
+
+    {
+        let requested_stab = requested.stabilization.read();
+        let mut stab = manager.stabilization.write();
+        stab.interpolation = requested_stab.interpolation;
+        stab.share_wgpu_instances = requested_stab.share_wgpu_instances;
+    }
+
+    let params = requested.params.read().clone();
+    manager.set_size(params.size.0, params.size.1);
+    manager.set_output_size(params.output_size.0, params.output_size.1);
+    manager.set_background_color(params.background);
+    *manager.params.write() = params;
+    manager
+}
+
+/// The per-instance state of a `CachedManager` or `CachedRender`, which is locked while the host's parameters
+/// are applied.
+#[derive(Default)]
+pub(crate) struct InstanceState {
+    pub has_recomputed: bool,
+}
+
+/// The state of the background recompute for a `CachedRender`.
+#[derive(Default)]
+struct RecomputeState {
+    running: bool,
+    pending_render: bool,
+    pending_smoothing: bool,
+}
+
+/// Values stored in a Gyroflow Project that the host can override on a per-frame basis.
+#[derive(Clone, Copy)]
+pub(crate) struct ProjectValues {
+    pub frame_readout_time: f64,
+    pub frame_readout_direction: ReadoutDirection,
+    pub adaptive_zoom_window: f64,
+    pub max_zoom: Option<f64>,
+    pub light_refraction_coefficient: f64,
+    pub additional_rotation: (f64, f64, f64),
+    pub additional_translation: (f64, f64, f64),
+}
+
+impl ProjectValues {
+    fn from_manager(manager: &StabilizationManager) -> Self {
+        let params = manager.params.read();
+        Self {
+            frame_readout_time: params.frame_readout_time,
+            frame_readout_direction: params.frame_readout_direction,
+            adaptive_zoom_window: params.adaptive_zoom_window,
+            max_zoom: params.max_zoom,
+            light_refraction_coefficient: params.light_refraction_coefficient,
+            additional_rotation: params.additional_rotation,
+            additional_translation: params.additional_translation,
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! # Gyroflow Toolbox: Lens Calibration
+//!
+//! This module calibrates a lens from a series of CPU chessboard frames, using the `gyroflow_core` lens calibrator,
+//! and writes out a lens profile JSON that `loadLensProfile` can use straight away.
+//!
+//! The `gyroflow_core` calibrator needs OpenCV, so this module is only available when the `calibration` feature
+//! is enabled.
+
+//---------------------------------------------------------
+// Local name bindings:
+//---------------------------------------------------------
+use gyroflow_core::calibration::LensCalibrator;
+use gyroflow_core::lens_profile::LensProfile;
+
+use lazy_static::*;                         // A macro for declaring lazily evaluated statics
+use serde::Serialize;                       // Allows us to serialize the calibration result
+use std::collections::HashMap;              // Allows us to use `HashMap`
+use std::sync::Arc;                         // Adds Atomic Reference Count support
+use std::sync::Mutex;                       // A mutual exclusion primitive useful for protecting shared data
+use std::sync::atomic::{ AtomicBool, AtomicUsize };
+
+//---------------------------------------------------------
+// The number of cells (in each direction) used to measure
+// how much of the frame the detected corners cover:
+//---------------------------------------------------------
+const COVERAGE_GRID_SIZE: usize = 10;
+
+//---------------------------------------------------------
+// The minimum number of frames with a detected chessboard
+// before we try to calibrate:
+//---------------------------------------------------------
+const MINIMUM_CALIBRATION_FRAMES: usize = 5;
+
+/// A calibration in progress.
+struct CalibrationSession {
+    calibrator: LensCalibrator,
+    width: usize,
+    height: usize,
+    frame_count: i32,
+}
+
+// The calibrations in progress, keyed by their identifier. Each session has its own lock, so that the (slow)
+// chessboard detection for one calibration doesn't block any other calibration.
+lazy_static! {
+    static ref CALIBRATIONS: Mutex<HashMap<String, Arc<Mutex<CalibrationSession>>>> = Mutex::new(HashMap::new());
+}
+
+/// The camera details written into the calibrated lens profile.
+pub(crate) struct CalibrationDetails {
+    pub camera_brand: String,
+    pub camera_model: String,
+    pub lens_model: String,
+    pub camera_setting: String,
+    pub fps: f64,
+}
+
+/// The result of a calibration.
+#[derive(Serialize)]
+pub(crate) struct CalibrationResult {
+    pub rms: f64,
+    pub coverage: f64,
+    pub frames_detected: usize,
+    pub frames_total: usize,
+    pub identifier: String,
+    pub lens_profile_path: String,
+}
+
+/// Starts a new calibration, replacing any calibration in progress with the same identifier.
+///
+/// # Arguments
+///
+/// * `identifier` - The identifier of the calibration.
+/// * `columns` - The number of inner corners across the chessboard.
+/// * `rows` - The number of inner corners down the chessboard.
+/// * `width` - The width of the frames.
+/// * `height` - The height of the frames.
+pub(crate) fn start(identifier: &str, columns: usize, rows: usize, width: usize, height: usize) -> Result<(), String> {
+    if columns < 3 || rows < 3 {
+        return Err(format!("The chessboard must have at least 3x3 inner corners, not {}x{}.", columns, rows));
+    }
+    if width == 0 || height == 0 {
+        return Err(format!("The frame size must be greater than zero, not {}x{}.", width, height));
+    }
+
+    let mut calibrator = LensCalibrator::new();
+    calibrator.columns = columns;
+    calibrator.rows = rows;
+    calibrator.width = width;
+    calibrator.height = height;
+
+    CALIBRATIONS.lock().unwrap().insert(identifier.to_string(), Arc::new(Mutex::new(CalibrationSession {
+        calibrator,
+        width,
+        height,
+        frame_count: 0,
+    })));
+    Ok(())
+}
+
+/// Adds a CPU frame to a calibration, and looks for the chessboard in it.
+///
+/// # Arguments
+///
+/// * `identifier` - The identifier of the calibration.
+/// * `timestamp_us` - The timestamp of the frame in microseconds.
+/// * `pixels` - The 8-bit pixels of the frame.
+/// * `bytes_per_row` - The number of bytes in each row of `pixels`.
+/// * `channels` - The number of channels in `pixels`, either 1 (grayscale) or 4 (BGRA/RGBA).
+///
+/// # Returns
+///
+/// `true` if the chessboard was found in the frame.
+pub(crate) fn add_frame(identifier: &str, timestamp_us: i64, pixels: &[u8], bytes_per_row: usize, channels: usize) -> Result<bool, String> {
+    let session = session(identifier)?;
+    let mut session = session.lock().unwrap();
+
+    if channels != 1 && channels != 4 {
+        return Err(format!("Frames must have 1 or 4 channels, not {}.", channels));
+    }
+    if bytes_per_row < session.width * channels || pixels.len() < bytes_per_row * session.height {
+        return Err("The frame is smaller than the calibration frame size.".to_string());
+    }
+
+    //---------------------------------------------------------
+    // The chessboard detection works on grayscale frames:
+    //---------------------------------------------------------
+    let grayscale: Vec<u8> = if channels == 1 {
+        (0..session.height).flat_map(|y| pixels[y * bytes_per_row..y * bytes_per_row + session.width].iter().copied()).collect()
+    } else {
+        (0..session.height).flat_map(|y| {
+            pixels[y * bytes_per_row..y * bytes_per_row + session.width * 4].chunks_exact(4).map(|pixel| {
+                ((pixel[0] as u32 + pixel[1] as u32 * 2 + pixel[2] as u32) / 4) as u8
+            })
+        }).collect()
+    };
+
+    let frame = session.frame_count;
+    session.frame_count += 1;
+
+    session.calibrator.feed_frame(
+        timestamp_us,
+        frame,
+        session.width as u32,
+        session.height as u32,
+        session.width,
+        &grayscale,
+        Arc::new(AtomicBool::new(false)),
+        0,
+        Arc::new(AtomicUsize::new(0)),
+        |_| (),
+    );
+
+    Ok(session.calibrator.image_points.read().contains_key(&frame))
+}
+
+/// Finishes a calibration, writing the lens profile JSON to `output_path`.
+///
+/// # Returns
+///
+/// The RMS error, how much of the frame the detected corners covered (from 0 to 1), and where the lens profile
+/// was written.
+pub(crate) fn finish(identifier: &str, output_path: &str, details: CalibrationDetails) -> Result<CalibrationResult, String> {
+    //---------------------------------------------------------
+    // Remove the session, then wait for any frame that's still
+    // being added to it:
+    //---------------------------------------------------------
+    let session = CALIBRATIONS.lock().unwrap().remove(identifier).ok_or_else(|| format!("There's no calibration in progress for: {}", identifier))?;
+    let mut session = session.lock().unwrap();
+
+    let frames_detected = session.calibrator.image_points.read().len();
+    if frames_detected < MINIMUM_CALIBRATION_FRAMES {
+        return Err(format!("The chessboard was only found in {} frame(s), but at least {} are needed.", frames_detected, MINIMUM_CALIBRATION_FRAMES));
+    }
+
+    //---------------------------------------------------------
+    // Calibrate:
+    //---------------------------------------------------------
+    session.calibrator.calibrate(false).map_err(|e| format!("Calibration failed: {:?}", e))?;
+    let rms = session.calibrator.rms;
+    if !rms.is_finite() {
+        return Err("Calibration failed to converge.".to_string());
+    }
+
+    //---------------------------------------------------------
+    // Build the Lens Profile:
+    //---------------------------------------------------------
+    let mut profile = LensProfile::default();
+    profile.camera_brand = details.camera_brand;
+    profile.camera_model = details.camera_model;
+    profile.lens_model = details.lens_model;
+    profile.camera_setting = details.camera_setting;
+    profile.fps = details.fps;
+    profile.calibrated_by = "Gyroflow Toolbox".to_string();
+    profile.set_from_calibrator(&session.calibrator);
+    if profile.identifier.is_empty() {
+        profile.identifier = calibrated_identifier(&profile);
+    }
+
+    let json = profile.get_json().map_err(|e| e.to_string())?;
+    std::fs::write(output_path, json).map_err(|e| format!("The lens profile couldn't be written: {}", e))?;
+
+    log::info!("[Gyroflow Toolbox Rust] Lens calibrated with an RMS error of {:.3} using {} frames: {}", rms, frames_detected, output_path);
+
+    Ok(CalibrationResult {
+        rms,
+        coverage: coverage(&session),
+        frames_detected,
+        frames_total: session.frame_count as usize,
+        identifier: profile.identifier.clone(),
+        lens_profile_path: output_path.to_string(),
+    })
+}
+
+/// Returns a calibration in progress. The `CALIBRATIONS` lock is only held while looking it up.
+fn session(identifier: &str) -> Result<Arc<Mutex<CalibrationSession>>, String> {
+    CALIBRATIONS.lock().unwrap().get(identifier).cloned().ok_or_else(|| format!("There's no calibration in progress for: {}", identifier))
+}
+
+/// Cancels a calibration in progress.
+pub(crate) fn cancel(identifier: &str) {
+    CALIBRATIONS.lock().unwrap().remove(identifier);
+}
+
+/// How much of the frame the detected chessboard corners cover, from 0 to 1. Good calibrations need corners right
+/// out to the edges of the frame, where the distortion is strongest.
+fn coverage(session: &CalibrationSession) -> f64 {
+    let mut covered = [[false; COVERAGE_GRID_SIZE]; COVERAGE_GRID_SIZE];
+    for detected in session.calibrator.image_points.read().values() {
+        for point in &detected.points {
+            let x = ((point.0 as f64 / session.width as f64) * COVERAGE_GRID_SIZE as f64) as usize;
+            let y = ((point.1 as f64 / session.height as f64) * COVERAGE_GRID_SIZE as f64) as usize;
+            covered[y.min(COVERAGE_GRID_SIZE - 1)][x.min(COVERAGE_GRID_SIZE - 1)] = true;
+        }
+    }
+    let covered_cells = covered.iter().flatten().filter(|cell| **cell).count();
+    covered_cells as f64 / (COVERAGE_GRID_SIZE * COVERAGE_GRID_SIZE) as f64
+}
+
+/// Builds an identifier for a calibrated lens profile from its camera details & calibration size.
+fn calibrated_identifier(profile: &LensProfile) -> String {
+    let name = format!("{} {} {} {} {}x{}", profile.camera_brand, profile.camera_model, profile.lens_model, profile.camera_setting, profile.calib_dimension.w, profile.calib_dimension.h);
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join("_")
+}
@@ -0,0 +1,163 @@
+//! # Gyroflow Toolbox: Keyframes
+//!
+//! This module allows the host to push full keyframe curves (times, values and easing) into the `gyroflow_core`
+//! `KeyframeManager`, so that animated parameters are evaluated by `gyroflow_core` rather than being overwritten
+//! on every frame.
+
+//---------------------------------------------------------
+// Local name bindings:
+//---------------------------------------------------------
+use gyroflow_core::StabilizationManager;
+use gyroflow_core::keyframes::{ Easing, KeyframeType };
+
+use lazy_static::*;                         // A macro for declaring lazily evaluated statics
+use serde::Deserialize;                     // Allows us to deserialize the keyframe curves
+use std::collections::HashMap;              // Allows us to use `HashMap`
+use std::sync::Arc;                         // Adds Atomic Reference Count support
+use std::sync::Mutex;                       // A mutual exclusion primitive useful for protecting shared data
+use std::sync::atomic::{ AtomicU64, Ordering };
+
+//---------------------------------------------------------
+// The parameters that can be keyframed, along with their
+// `gyroflow_core` keyframe type, and the scale between the
+// `processFrame` value and the `gyroflow_core` value:
+//---------------------------------------------------------
+const KEYFRAMED_PARAMETERS: [(&str, KeyframeType, f64); 7] = [
+    ("fov",                 KeyframeType::Fov,                          1.0),
+    ("smoothness",          KeyframeType::SmoothingParamSmoothness,     1.0),
+    ("lens_correction",     KeyframeType::LensCorrectionStrength,       1.0),
+    ("horizon_lock",        KeyframeType::LockHorizonAmount,            1.0),
+    ("horizon_roll",        KeyframeType::LockHorizonRoll,              1.0),
+    ("position_offset_x",   KeyframeType::ZoomingCenterX,               0.01),
+    ("position_offset_y",   KeyframeType::ZoomingCenterY,               0.01),
+];
+
+/// A single keyframe, as supplied by the host.
+#[derive(Deserialize)]
+pub(crate) struct Keyframe {
+    pub timestamp_us: i64,
+    pub value: f64,
+    #[serde(default)]
+    pub easing: Option<String>,
+}
+
+/// The keyframe curves for a single instance, keyed by `processFrame` parameter name.
+pub(crate) type KeyframeCurves = HashMap<String, Vec<Keyframe>>;
+
+// The latest keyframe curves for each unique identifier, along with their revision.
+lazy_static! {
+    static ref KEYFRAME_CURVES: Mutex<HashMap<String, (u64, Arc<KeyframeCurves>)>> = Mutex::new(HashMap::new());
+}
+
+// Incremented every time new keyframe curves are supplied.
+static KEYFRAME_REVISION: AtomicU64 = AtomicU64::new(1);
+
+/// Converts an easing name to a `gyroflow_core` `Easing`.
+fn easing_from_name(name: &str) -> Option<Easing> {
+    match name {
+        "None" | "Linear" | "NoEasing" => Some(Easing::NoEasing),
+        "EaseIn" => Some(Easing::EaseIn),
+        "EaseOut" => Some(Easing::EaseOut),
+        "EaseInOut" => Some(Easing::EaseInOut),
+        _ => None,
+    }
+}
+
+/// Parses and validates keyframe curves supplied as JSON, i.e.
+///
+/// ```json
+/// { "smoothness": [ { "timestamp_us": 0, "value": 0.5, "easing": "EaseInOut" }, { "timestamp_us": 2000000, "value": 1.0 } ] }
+/// ```
+pub(crate) fn parse_keyframe_curves(json: &str) -> Result<KeyframeCurves, String> {
+    let curves: KeyframeCurves = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    for (name, keyframes) in curves.iter() {
+        if !KEYFRAMED_PARAMETERS.iter().any(|(parameter, _, _)| parameter == name) {
+            return Err(format!("Unsupported keyframe parameter: {}", name));
+        }
+        for keyframe in keyframes {
+            if let Some(easing) = &keyframe.easing {
+                if easing_from_name(easing).is_none() {
+                    return Err(format!("Unsupported keyframe easing: {}", easing));
+                }
+            }
+        }
+    }
+    Ok(curves)
+}
+
+/// Stores the keyframe curves for a unique identifier. They'll be applied to the `StabilizationManager` the next
+/// time the instance is rendered.
+///
+/// The curves are host state, so they're kept when the cache is trashed (and applied again to the new manager).
+/// Storing empty curves removes the keyframes.
+pub(crate) fn set_keyframe_curves(unique_identifier: &str, curves: KeyframeCurves) {
+    let revision = KEYFRAME_REVISION.fetch_add(1, Ordering::SeqCst);
+    KEYFRAME_CURVES.lock().unwrap().insert(unique_identifier.to_string(), (revision, Arc::new(curves)));
+}
+
+/// Removes the keyframe curves for a unique identifier. If the instance is still rendered, its keyframes are
+/// removed from the `StabilizationManager` the next time it's rendered.
+pub(crate) fn remove_keyframe_curves(unique_identifier: &str) {
+    KEYFRAME_CURVES.lock().unwrap().remove(unique_identifier);
+}
+
+/// Applies the keyframe curves for a unique identifier to the `StabilizationManager`, if they've changed since
+/// they were last applied.
+///
+/// # Returns
+///
+/// `true` if the keyframes were changed, so the smoothing needs to be recomputed.
+pub(crate) fn apply_keyframe_curves_if_changed(unique_identifier: &str, manager: &StabilizationManager, applied_revision: &AtomicU64) -> bool {
+    let (revision, curves) = {
+        let keyframe_curves = KEYFRAME_CURVES.lock().unwrap();
+        match keyframe_curves.get(unique_identifier) {
+            Some((revision, curves)) => (*revision, curves.clone()),
+            None => (0, Arc::new(KeyframeCurves::new())),
+        }
+    };
+
+    if applied_revision.swap(revision, Ordering::SeqCst) == revision {
+        return false;
+    }
+
+    let mut keyframes = manager.keyframes.write();
+    for (name, keyframe_type, scale) in KEYFRAMED_PARAMETERS.iter() {
+        keyframes.clear_type(keyframe_type);
+        if let Some(curve) = curves.get(*name) {
+            for keyframe in curve {
+                keyframes.set(keyframe_type, keyframe.timestamp_us, keyframe.value * scale);
+                if let Some(easing) = keyframe.easing.as_deref().and_then(easing_from_name) {
+                    keyframes.set_easing(keyframe_type, keyframe.timestamp_us, easing);
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Which `processFrame` parameters are currently animated by keyframes, and therefore shouldn't be overwritten
+/// by the per-frame values from the host.
+pub(crate) struct KeyframedParameters {
+    pub fov: bool,
+    pub smoothness: bool,
+    pub lens_correction: bool,
+    pub horizon_lock_amount: bool,
+    pub horizon_roll: bool,
+    pub position_offset_x: bool,
+    pub position_offset_y: bool,
+}
+
+impl KeyframedParameters {
+    pub(crate) fn from_manager(manager: &StabilizationManager) -> Self {
+        let keyframes = manager.keyframes.read();
+        Self {
+            fov: keyframes.is_keyframed(&KeyframeType::Fov),
+            smoothness: keyframes.is_keyframed(&KeyframeType::SmoothingParamSmoothness),
+            lens_correction: keyframes.is_keyframed(&KeyframeType::LensCorrectionStrength),
+            horizon_lock_amount: keyframes.is_keyframed(&KeyframeType::LockHorizonAmount),
+            horizon_roll: keyframes.is_keyframed(&KeyframeType::LockHorizonRoll),
+            position_offset_x: keyframes.is_keyframed(&KeyframeType::ZoomingCenterX),
+            position_offset_y: keyframes.is_keyframed(&KeyframeType::ZoomingCenterY),
+        }
+    }
+}
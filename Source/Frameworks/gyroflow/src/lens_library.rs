@@ -0,0 +1,235 @@
+//! # Gyroflow Toolbox: Lens Profile Library
+//!
+//! This module owns the lens profile database that's shared by every `StabilizationManager` created by the bridge.
+//! It's loaded lazily the first time it's needed (or explicitly via `loadLensLibrary`), and isn't stored in the
+//! `MANAGER_CACHE`, so it can't be evicted or purged.
+//!
+//! Extra user lens profile directories & files can be registered, which are merged on top of the bundled lens
+//! profiles (so a user profile replaces a bundled profile with the same identifier).
+
+//---------------------------------------------------------
+// Local name bindings:
+//---------------------------------------------------------
+use gyroflow_core::StabilizationManager;
+use gyroflow_core::lens_profile::LensProfile;
+use gyroflow_core::lens_profile_database::LensProfileDatabase;
+
+use lazy_static::*;                         // A macro for declaring lazily evaluated statics
+use parking_lot::RwLock;                    // The reader-writer lock used by `gyroflow_core`
+use serde::Serialize;                       // Allows us to serialize the loading report
+use std::collections::HashSet;              // Allows us to use `HashSet`
+use std::path::{ Path, PathBuf };           // Allows us to use `Path` & `PathBuf`
+use std::sync::Arc;                         // Adds Atomic Reference Count support
+use std::sync::Mutex;                       // A mutual exclusion primitive useful for protecting shared data
+
+// The shared lens profile database. Reloading replaces its contents in place, so every `StabilizationManager`
+// that's already using it sees the new profiles.
+lazy_static! {
+    static ref LENS_LIBRARY: Arc<RwLock<LensProfileDatabase>> = Arc::new(RwLock::new(LensProfileDatabase::default()));
+}
+
+// Held while the lens profiles are loaded from disk, so that they're only loaded once.
+static LOADING: Mutex<()> = Mutex::new(());
+
+// The registered user lens profile directories & files, in the order they were registered.
+static USER_LENS_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// A lens profile file that couldn't be loaded.
+#[derive(Serialize)]
+pub(crate) struct LensProfileLoadFailure {
+    pub path: String,
+    pub error: String,
+}
+
+/// The result of loading user lens profiles.
+#[derive(Default, Serialize)]
+pub(crate) struct UserLensProfileReport {
+    pub loaded: Vec<String>,
+    pub overridden: Vec<String>,
+    pub failed: Vec<LensProfileLoadFailure>,
+}
+
+/// Loads all the lens profiles into the shared lens profile database, if they haven't been loaded yet.
+///
+/// # Returns
+///
+/// `true` if the lens profiles are loaded.
+pub(crate) fn load() -> bool {
+    //---------------------------------------------------------
+    // Once loaded, don't wait behind a reload or a user lens
+    // profile path that's being added:
+    //---------------------------------------------------------
+    if LENS_LIBRARY.read().loaded {
+        return true;
+    }
+
+    let _loading = LOADING.lock().unwrap();
+    if !LENS_LIBRARY.read().loaded {
+        load_from_disk();
+    }
+    LENS_LIBRARY.read().loaded
+}
+
+/// Reloads all the lens profiles from disk into the shared lens profile database.
+///
+/// # Returns
+///
+/// `true` if the lens profiles are loaded.
+pub(crate) fn reload() -> bool {
+    let _loading = LOADING.lock().unwrap();
+    load_from_disk();
+    LENS_LIBRARY.read().loaded
+}
+
+/// Loads the lens profiles into a new database, then swaps it in, so that readers aren't blocked while loading.
+fn load_from_disk() {
+    log::info!("[Gyroflow Toolbox Rust] Loading the Lens Profile Library...");
+    let mut db = LensProfileDatabase::default();
+    db.load_all();
+
+    //---------------------------------------------------------
+    // Merge the user lens profiles on top:
+    //---------------------------------------------------------
+    let user_lens_paths = USER_LENS_PATHS.lock().unwrap().clone();
+    let mut report = UserLensProfileReport::default();
+    for path in user_lens_paths {
+        let profiles = load_user_path(&path, &mut report);
+        merge_user_profiles(&mut db, profiles, &mut report);
+    }
+    log_report(&report);
+
+    *LENS_LIBRARY.write() = db;
+}
+
+/// Registers a user lens profile directory (searched recursively for `.json` files) or a single lens profile file,
+/// and merges its lens profiles into the shared lens profile database. The path is also loaded again whenever the
+/// library is reloaded.
+///
+/// # Returns
+///
+/// Which lens profiles were loaded, which bundled lens profiles they replaced, and which files failed to load.
+pub(crate) fn add_user_path(path: &Path) -> UserLensProfileReport {
+    let _loading = LOADING.lock().unwrap();
+    if !LENS_LIBRARY.read().loaded {
+        load_from_disk();
+    }
+
+    {
+        let mut user_lens_paths = USER_LENS_PATHS.lock().unwrap();
+        if !user_lens_paths.iter().any(|existing| existing == path) {
+            user_lens_paths.push(path.to_path_buf());
+        }
+    }
+
+    //---------------------------------------------------------
+    // Read the files before taking the write lock, so that
+    // renders aren't blocked by the disk access:
+    //---------------------------------------------------------
+    let mut report = UserLensProfileReport::default();
+    let profiles = load_user_path(path, &mut report);
+    merge_user_profiles(&mut LENS_LIBRARY.write(), profiles, &mut report);
+    log_report(&report);
+    report
+}
+
+/// Removes all the registered user lens profile paths, and reloads the bundled lens profiles.
+pub(crate) fn clear_user_paths() -> bool {
+    USER_LENS_PATHS.lock().unwrap().clear();
+    reload()
+}
+
+/// A user lens profile that's been read from disk, but not merged into a lens profile database yet.
+struct UserLensProfile {
+    key: String,
+    path: String,
+    profile: LensProfile,
+}
+
+/// Reads the lens profiles in a user lens profile directory (recursively) or file.
+///
+/// Each directory is only visited once (by its canonical path), so symbolic links that point back up the tree
+/// can't cause an endless loop.
+fn load_user_path(path: &Path, report: &mut UserLensProfileReport) -> Vec<UserLensProfile> {
+    let mut profiles = Vec::new();
+    let mut visited_directories = HashSet::new();
+    load_user_path_into(path, &mut visited_directories, &mut profiles, report);
+    profiles
+}
+
+/// Reads the lens profiles in a user lens profile directory or file, skipping directories that were already visited.
+fn load_user_path_into(path: &Path, visited_directories: &mut HashSet<PathBuf>, profiles: &mut Vec<UserLensProfile>, report: &mut UserLensProfileReport) {
+    if path.is_dir() {
+        let canonical_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if !visited_directories.insert(canonical_path) {
+            log::info!("[Gyroflow Toolbox Rust] Skipping already visited Lens Profile directory: {:?}", path);
+            return;
+        }
+
+        match std::fs::read_dir(path) {
+            Ok(entries) => {
+                let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+                paths.sort();
+                for child in paths {
+                    let is_json = child.extension().map(|extension| extension.eq_ignore_ascii_case("json")).unwrap_or(false);
+                    if child.is_dir() || is_json {
+                        load_user_path_into(&child, visited_directories, profiles, report);
+                    }
+                }
+            },
+            Err(e) => {
+                report.failed.push(LensProfileLoadFailure { path: path.to_string_lossy().to_string(), error: e.to_string() });
+            }
+        }
+        return;
+    }
+
+    let path_string = path.to_string_lossy().to_string();
+    match load_user_lens_profile(path) {
+        Ok(mut profile) => {
+            let key = if profile.identifier.is_empty() { path_string.clone() } else { profile.identifier.clone() };
+            profile.filename = path_string.clone();
+            profiles.push(UserLensProfile { key, path: path_string, profile });
+        },
+        Err(error) => {
+            report.failed.push(LensProfileLoadFailure { path: path_string, error });
+        }
+    }
+}
+
+/// Merges user lens profiles into a lens profile database, replacing any profiles with the same identifier.
+fn merge_user_profiles(db: &mut LensProfileDatabase, profiles: Vec<UserLensProfile>, report: &mut UserLensProfileReport) {
+    for UserLensProfile { key, path, profile } in profiles {
+        if db.get_by_id(&key).is_some() {
+            report.overridden.push(key.clone());
+        }
+        db.map.insert(key, profile);
+        report.loaded.push(path);
+    }
+}
+
+/// Reads & parses a single lens profile file.
+fn load_user_lens_profile(path: &Path) -> Result<LensProfile, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    LensProfile::from_json(&json).map_err(|e| e.to_string())
+}
+
+/// Logs the result of loading user lens profiles.
+fn log_report(report: &UserLensProfileReport) {
+    if !report.loaded.is_empty() {
+        log::info!("[Gyroflow Toolbox Rust] Loaded {} user Lens Profile(s), replacing {} bundled Lens Profile(s).", report.loaded.len(), report.overridden.len());
+    }
+    for failure in &report.failed {
+        log::error!("[Gyroflow Toolbox Rust] Failed to load user Lens Profile {:?}: {}", failure.path, failure.error);
+    }
+}
+
+/// Returns the shared lens profile database, loading it first if needed.
+pub(crate) fn lens_profile_db() -> Arc<RwLock<LensProfileDatabase>> {
+    load();
+    LENS_LIBRARY.clone()
+}
+
+/// Uses the shared lens profile database for the supplied `StabilizationManager`.
+pub(crate) fn use_lens_library(stab: &mut StabilizationManager) {
+    stab.lens_profile_db = lens_profile_db();
+}
@@ -0,0 +1,521 @@
+//! # Gyroflow Toolbox: Lens Profile Search
+//!
+//! This module lists the lens profiles in the shared Lens Profile Library, and supports filtered & fuzzy searching,
+//! so that the plugin can offer a searchable lens picker instead of a file dialog. It can also suggest lens
+//! profiles from the camera metadata of a clip.
+
+//---------------------------------------------------------
+// Local name bindings:
+//---------------------------------------------------------
+use gyroflow_core::StabilizationManager;
+use gyroflow_core::lens_profile_database::LensProfileDatabase;
+
+use serde::{ Deserialize, Serialize };      // Allows us to (de)serialize the lens profiles & filters
+
+//---------------------------------------------------------
+// The minimum fuzzy match score for a search result:
+//---------------------------------------------------------
+const MINIMUM_SEARCH_SCORE: f64 = 0.3;
+
+//---------------------------------------------------------
+// The minimum length of a query word for typos to be
+// allowed:
+//---------------------------------------------------------
+const MINIMUM_TYPO_WORD_LENGTH: usize = 3;
+
+//---------------------------------------------------------
+// The weights used when suggesting lens profiles from clip
+// metadata (they add up to 1):
+//---------------------------------------------------------
+const SUGGESTION_BRAND_WEIGHT: f64 = 0.25;
+const SUGGESTION_MODEL_WEIGHT: f64 = 0.3;
+const SUGGESTION_LENS_WEIGHT: f64 = 0.1;
+const SUGGESTION_RESOLUTION_WEIGHT: f64 = 0.2;
+const SUGGESTION_FPS_WEIGHT: f64 = 0.1;
+const SUGGESTION_QUALITY_WEIGHT: f64 = 0.05;
+
+/// A lens profile in the Lens Profile Library, as returned to the host.
+#[derive(Clone, Serialize)]
+pub(crate) struct LensProfileInfo {
+    pub identifier: String,
+    pub name: String,
+    pub brand: String,
+    pub model: String,
+    pub lens: String,
+    pub setting: String,
+    pub width: usize,
+    pub height: usize,
+    pub fps: f64,
+    pub rating: f64,
+    pub official: bool,
+}
+
+/// A lens profile search result, along with its match score (0 to 1).
+#[derive(Serialize)]
+pub(crate) struct LensProfileMatch {
+    #[serde(flatten)]
+    pub profile: LensProfileInfo,
+    pub score: f64,
+}
+
+/// Filters for listing & searching lens profiles. Text filters are case-insensitive substring matches, and any
+/// missing field matches everything.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct LensProfileFilter {
+    pub brand: Option<String>,
+    pub model: Option<String>,
+    pub lens: Option<String>,
+    pub setting: Option<String>,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub fps: Option<f64>,
+    pub official_only: bool,
+    pub minimum_rating: Option<f64>,
+}
+
+impl LensProfileFilter {
+    /// Parses a filter from JSON. An empty string means no filter.
+    pub(crate) fn from_json(json: &str) -> Result<Self, String> {
+        if json.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    fn matches(&self, profile: &LensProfileInfo) -> bool {
+        let text_matches = |filter: &Option<String>, value: &str| {
+            filter.as_ref().map(|filter| value.to_lowercase().contains(&filter.to_lowercase())).unwrap_or(true)
+        };
+
+        text_matches(&self.brand, &profile.brand)
+            && text_matches(&self.model, &profile.model)
+            && text_matches(&self.lens, &profile.lens)
+            && text_matches(&self.setting, &profile.setting)
+            && self.width.map(|width| width == profile.width).unwrap_or(true)
+            && self.height.map(|height| height == profile.height).unwrap_or(true)
+            && self.fps.map(|fps| (fps - profile.fps).abs() < 0.5).unwrap_or(true)
+            && (!self.official_only || profile.official)
+            && self.minimum_rating.map(|rating| profile.rating >= rating).unwrap_or(true)
+    }
+}
+
+/// Lists all the lens profiles in a lens profile database.
+pub(crate) fn all_profiles(db: &LensProfileDatabase) -> Vec<LensProfileInfo> {
+    let mut profiles: Vec<LensProfileInfo> = db.get_all_info().into_iter().filter_map(|(name, key, _checksum, official, rating, _aspect_ratio)| {
+        let profile = db.get_by_id(&key)?;
+        Some(LensProfileInfo {
+            identifier: if profile.identifier.is_empty() { key } else { profile.identifier.clone() },
+            name,
+            brand: profile.camera_brand.clone(),
+            model: profile.camera_model.clone(),
+            lens: profile.lens_model.clone(),
+            setting: profile.camera_setting.clone(),
+            width: profile.calib_dimension.w,
+            height: profile.calib_dimension.h,
+            fps: profile.fps,
+            rating,
+            official,
+        })
+    }).collect();
+
+    profiles.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    profiles
+}
+
+/// Lists the lens profiles that match the filter.
+pub(crate) fn filtered_profiles(db: &LensProfileDatabase, filter: &LensProfileFilter) -> Vec<LensProfileInfo> {
+    all_profiles(db).into_iter().filter(|profile| filter.matches(profile)).collect()
+}
+
+/// Fuzzy searches the lens profiles that match the filter. Results are sorted by score, then rating.
+///
+/// Every word in the query has to (approximately) match a word in the brand, model, lens or setting, so that
+/// typos & partial words still find the profile.
+pub(crate) fn search_profiles(db: &LensProfileDatabase, query: &str, filter: &LensProfileFilter, max_results: usize) -> Vec<LensProfileMatch> {
+    rank_search_results(filtered_profiles(db, filter), query, max_results)
+}
+
+/// Scores & sorts lens profiles against a fuzzy search query, dropping the ones that don't match.
+fn rank_search_results(profiles: Vec<LensProfileInfo>, query: &str, max_results: usize) -> Vec<LensProfileMatch> {
+    let query_words = words(query);
+
+    let mut matches: Vec<LensProfileMatch> = profiles.into_iter().filter_map(|profile| {
+        let score = if query_words.is_empty() {
+            1.0
+        } else {
+            let profile_words = words(&format!("{} {} {} {} {}", profile.brand, profile.model, profile.lens, profile.setting, profile.name));
+            let mut total = 0.0;
+            for query_word in &query_words {
+                let best = profile_words.iter().map(|word| word_similarity(query_word, word)).fold(0.0, f64::max);
+                if best == 0.0 {
+                    return None;
+                }
+                total += best;
+            }
+            total / query_words.len() as f64
+        };
+        (score >= MINIMUM_SEARCH_SCORE).then_some(LensProfileMatch { profile, score })
+    }).collect();
+
+    matches.sort_by(|a, b| {
+        b.score.total_cmp(&a.score).then(b.profile.rating.total_cmp(&a.profile.rating))
+    });
+    if max_results > 0 {
+        matches.truncate(max_results);
+    }
+    matches
+}
+
+/// Splits text into lowercase alphanumeric words.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+/// How similar a (lowercase) query word is to a (lowercase) word, from 0 (no match) to 1 (exact match).
+fn word_similarity(query: &str, word: &str) -> f64 {
+    if query == word {
+        return 1.0;
+    }
+    if word.starts_with(query) {
+        return 0.8;
+    }
+    if word.contains(query) {
+        return 0.6;
+    }
+
+    //---------------------------------------------------------
+    // Allow for typos, i.e. "hreo" for "hero". Short words
+    // like "gpo" still get a single edit, but one & two letter
+    // words would match almost anything:
+    //---------------------------------------------------------
+    let query_length = query.chars().count();
+    if query_length >= MINIMUM_TYPO_WORD_LENGTH {
+        let allowed_edits = (query_length / 4).max(1);
+        let distance = edit_distance(query, word);
+        if distance <= allowed_edits {
+            return 0.5 * (1.0 - distance as f64 / query_length as f64);
+        }
+    }
+    0.0
+}
+
+/// The edit distance between two strings, counting an insertion, deletion, substitution or transposition of two
+/// adjacent characters as a single edit (the optimal string alignment distance).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = (distances[i - 1][j - 1] + cost)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+/// The camera metadata of a clip, as detected by `gyroflow_core` when importing a media file or Gyroflow Project.
+#[derive(Default, Serialize)]
+pub(crate) struct ClipMetadata {
+    pub brand: String,
+    pub model: String,
+    pub lens: String,
+    pub width: usize,
+    pub height: usize,
+    pub fps: f64,
+}
+
+impl ClipMetadata {
+    pub(crate) fn from_manager(manager: &StabilizationManager) -> Self {
+        let params = manager.params.read();
+        let mut metadata = Self {
+            width: params.video_size.0,
+            height: params.video_size.1,
+            fps: params.fps,
+            ..Default::default()
+        };
+        if let Some(camera_id) = manager.camera_id.read().as_ref() {
+            metadata.brand = camera_id.brand.clone();
+            metadata.model = camera_id.model.clone();
+            metadata.lens = camera_id.lens_model.clone();
+        }
+        metadata
+    }
+}
+
+/// Suggests lens profiles for a clip, ranked by how well they match the clip metadata. Profiles from a different
+/// camera brand are never suggested.
+pub(crate) fn suggest_profiles(db: &LensProfileDatabase, clip: &ClipMetadata, max_results: usize) -> Vec<LensProfileMatch> {
+    rank_suggestions(all_profiles(db), clip, max_results)
+}
+
+/// Scores & sorts lens profiles against the clip metadata, dropping the ones that can't match.
+fn rank_suggestions(profiles: Vec<LensProfileInfo>, clip: &ClipMetadata, max_results: usize) -> Vec<LensProfileMatch> {
+    let mut matches: Vec<LensProfileMatch> = profiles.into_iter().filter_map(|profile| {
+        //---------------------------------------------------------
+        // Brand:
+        //---------------------------------------------------------
+        let brand_score = text_similarity(&clip.brand, &profile.brand);
+        if !clip.brand.is_empty() && brand_score == 0.0 {
+            return None;
+        }
+
+        //---------------------------------------------------------
+        // Model & Lens:
+        //---------------------------------------------------------
+        let model_score = text_similarity(&clip.model, &profile.model);
+        let lens_score = text_similarity(&clip.lens, &format!("{} {}", profile.lens, profile.setting));
+
+        //---------------------------------------------------------
+        // Resolution (the same aspect ratio is a partial match):
+        //---------------------------------------------------------
+        let resolution_score = if clip.width == 0 || clip.height == 0 || profile.width == 0 || profile.height == 0 {
+            0.0
+        } else if clip.width == profile.width && clip.height == profile.height {
+            1.0
+        } else if ((clip.width as f64 / clip.height as f64) - (profile.width as f64 / profile.height as f64)).abs() < 0.01 {
+            0.5
+        } else {
+            0.0
+        };
+
+        //---------------------------------------------------------
+        // Frame Rate:
+        //---------------------------------------------------------
+        let fps_score = if clip.fps > 0.0 && (clip.fps - profile.fps).abs() < 0.5 { 1.0 } else { 0.0 };
+
+        //---------------------------------------------------------
+        // Prefer official & highly rated profiles:
+        //---------------------------------------------------------
+        let quality_score = if profile.official { 0.5 } else { 0.0 } + (profile.rating / 10.0).clamp(0.0, 0.5);
+
+        let score = brand_score * SUGGESTION_BRAND_WEIGHT
+            + model_score * SUGGESTION_MODEL_WEIGHT
+            + lens_score * SUGGESTION_LENS_WEIGHT
+            + resolution_score * SUGGESTION_RESOLUTION_WEIGHT
+            + fps_score * SUGGESTION_FPS_WEIGHT
+            + quality_score * SUGGESTION_QUALITY_WEIGHT;
+
+        (score > 0.0).then_some(LensProfileMatch { profile, score })
+    }).collect();
+
+    matches.sort_by(|a, b| {
+        b.score.total_cmp(&a.score).then(b.profile.rating.total_cmp(&a.profile.rating))
+    });
+    if max_results > 0 {
+        matches.truncate(max_results);
+    }
+    matches
+}
+
+/// Returns the best suggestion if it should be applied automatically, i.e. its score is at least the threshold.
+/// A `NaN` threshold never auto-applies.
+pub(crate) fn auto_apply_suggestion(suggestions: &[LensProfileMatch], auto_apply_threshold: f64) -> Option<&LensProfileMatch> {
+    if auto_apply_threshold.is_nan() {
+        return None;
+    }
+    suggestions.first().filter(|best| best.score >= auto_apply_threshold)
+}
+
+/// How similar two pieces of text are, from 0 to 1, as the average of the best match for each word in `query`.
+fn text_similarity(query: &str, text: &str) -> f64 {
+    let query_words = words(query);
+    let text_words = words(text);
+    if query_words.is_empty() || text_words.is_empty() {
+        return 0.0;
+    }
+
+    let total: f64 = query_words.iter().map(|query_word| {
+        text_words.iter().map(|word| word_similarity(query_word, word)).fold(0.0, f64::max)
+    }).sum();
+    total / query_words.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(identifier: &str, brand: &str, model: &str, lens: &str, rating: f64) -> LensProfileInfo {
+        LensProfileInfo {
+            identifier: identifier.to_string(),
+            name: format!("{} {} {}", brand, model, lens),
+            brand: brand.to_string(),
+            model: model.to_string(),
+            lens: lens.to_string(),
+            setting: String::new(),
+            width: 3840,
+            height: 2160,
+            fps: 29.97,
+            rating,
+            official: false,
+        }
+    }
+
+    #[test]
+    fn word_similarity_ranks_exact_prefix_and_substring_matches() {
+        assert_eq!(word_similarity("hero", "hero"), 1.0);
+        assert_eq!(word_similarity("her", "hero"), 0.8);
+        assert_eq!(word_similarity("ero", "hero"), 0.6);
+    }
+
+    #[test]
+    fn word_similarity_allows_transposed_letters() {
+        let score = word_similarity("hreo", "hero");
+        assert!(score > 0.0 && score < 0.6, "score was {}", score);
+    }
+
+    #[test]
+    fn word_similarity_allows_a_typo_in_short_words() {
+        assert!(word_similarity("gpo", "gop") > 0.0);
+        assert!(word_similarity("sny", "sony") > 0.0);
+    }
+
+    #[test]
+    fn word_similarity_rejects_very_short_and_unrelated_words() {
+        assert_eq!(word_similarity("ab", "ac"), 0.0);
+        assert_eq!(word_similarity("hero", "sony"), 0.0);
+        assert_eq!(word_similarity("hero", "hroe11"), 0.0);
+    }
+
+    #[test]
+    fn edit_distance_counts_a_transposition_as_one_edit() {
+        assert_eq!(edit_distance("hreo", "hero"), 1);
+        assert_eq!(edit_distance("hero", "hero"), 0);
+        assert_eq!(edit_distance("hero", "heroes"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn search_requires_every_query_word_to_match() {
+        let profiles = vec![
+            profile("gopro_hero9", "GoPro", "HERO9 Black", "Wide", 5.0),
+            profile("sony_a7s3", "Sony", "a7S III", "FE 24mm", 5.0),
+        ];
+        let results = rank_search_results(profiles, "gopro hreo9", 0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].profile.identifier, "gopro_hero9");
+    }
+
+    #[test]
+    fn search_sorts_by_score_then_rating() {
+        let profiles = vec![
+            profile("hero_low", "GoPro", "HERO10", "Wide", 1.0),
+            profile("hero_high", "GoPro", "HERO10", "Wide", 4.0),
+            profile("hero_exact", "GoPro", "HERO", "Wide", 0.0),
+        ];
+        let results = rank_search_results(profiles, "hero", 0);
+        let identifiers: Vec<&str> = results.iter().map(|result| result.profile.identifier.as_str()).collect();
+        assert_eq!(identifiers, vec!["hero_exact", "hero_high", "hero_low"]);
+    }
+
+    #[test]
+    fn search_with_an_empty_query_returns_everything_up_to_the_limit() {
+        let profiles = vec![
+            profile("a", "GoPro", "HERO9", "Wide", 1.0),
+            profile("b", "GoPro", "HERO10", "Wide", 2.0),
+            profile("c", "Sony", "a7S III", "FE 24mm", 3.0),
+        ];
+        let results = rank_search_results(profiles, "", 2);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| result.score == 1.0));
+        assert_eq!(results[0].profile.identifier, "c");
+    }
+
+    fn clip(brand: &str, model: &str, width: usize, height: usize, fps: f64) -> ClipMetadata {
+        ClipMetadata {
+            brand: brand.to_string(),
+            model: model.to_string(),
+            lens: String::new(),
+            width,
+            height,
+            fps,
+        }
+    }
+
+    fn suggestion(identifier: &str, score: f64) -> LensProfileMatch {
+        LensProfileMatch { profile: profile(identifier, "GoPro", "HERO9", "Wide", 0.0), score }
+    }
+
+    #[test]
+    fn suggestions_never_include_a_different_brand() {
+        let profiles = vec![
+            profile("gopro", "GoPro", "HERO9 Black", "Wide", 5.0),
+            profile("sony", "Sony", "HERO9 Black", "Wide", 5.0),
+        ];
+        let results = rank_suggestions(profiles, &clip("GoPro", "HERO9 Black", 3840, 2160, 29.97), 0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].profile.identifier, "gopro");
+    }
+
+    #[test]
+    fn suggestions_prefer_the_matching_model() {
+        let profiles = vec![
+            profile("hero8", "GoPro", "HERO8 Black", "Wide", 5.0),
+            profile("hero9", "GoPro", "HERO9 Black", "Wide", 0.0),
+        ];
+        let results = rank_suggestions(profiles, &clip("GoPro", "HERO9 Black", 3840, 2160, 29.97), 0);
+        assert_eq!(results[0].profile.identifier, "hero9");
+        assert!(results[0].score > results[1].score);
+    }
+
+    #[test]
+    fn suggestions_prefer_the_matching_resolution_then_aspect_ratio() {
+        let mut exact = profile("exact", "GoPro", "HERO9 Black", "Wide", 0.0);
+        exact.width = 3840;
+        exact.height = 2160;
+        let mut same_aspect_ratio = profile("same_aspect_ratio", "GoPro", "HERO9 Black", "Wide", 0.0);
+        same_aspect_ratio.width = 1920;
+        same_aspect_ratio.height = 1080;
+        let mut other = profile("other", "GoPro", "HERO9 Black", "Wide", 0.0);
+        other.width = 2704;
+        other.height = 2028;
+
+        let results = rank_suggestions(vec![other, same_aspect_ratio, exact], &clip("GoPro", "HERO9 Black", 3840, 2160, 29.97), 0);
+        let identifiers: Vec<&str> = results.iter().map(|result| result.profile.identifier.as_str()).collect();
+        assert_eq!(identifiers, vec!["exact", "same_aspect_ratio", "other"]);
+        assert!((results[0].score - results[1].score - 0.5 * SUGGESTION_RESOLUTION_WEIGHT).abs() < 1e-9);
+    }
+
+    #[test]
+    fn suggestions_prefer_the_matching_frame_rate() {
+        let mut matching = profile("matching", "GoPro", "HERO9 Black", "Wide", 0.0);
+        matching.fps = 59.94;
+        let other = profile("other", "GoPro", "HERO9 Black", "Wide", 0.0);
+
+        let results = rank_suggestions(vec![other, matching], &clip("GoPro", "HERO9 Black", 3840, 2160, 59.94), 0);
+        assert_eq!(results[0].profile.identifier, "matching");
+        assert!((results[0].score - results[1].score - SUGGESTION_FPS_WEIGHT).abs() < 1e-9);
+    }
+
+    #[test]
+    fn auto_apply_uses_the_best_suggestion_at_or_above_the_threshold() {
+        let suggestions = vec![suggestion("best", 0.8), suggestion("second", 0.5)];
+        assert_eq!(auto_apply_suggestion(&suggestions, 0.8).map(|best| best.profile.identifier.as_str()), Some("best"));
+        assert_eq!(auto_apply_suggestion(&suggestions, 0.5).map(|best| best.profile.identifier.as_str()), Some("best"));
+        assert!(auto_apply_suggestion(&suggestions, 0.9).is_none());
+    }
+
+    #[test]
+    fn auto_apply_is_disabled_by_nan_or_no_suggestions() {
+        let suggestions = vec![suggestion("best", 1.0)];
+        assert!(auto_apply_suggestion(&suggestions, f64::NAN).is_none());
+        assert!(auto_apply_suggestion(&[], 0.0).is_none());
+    }
+}
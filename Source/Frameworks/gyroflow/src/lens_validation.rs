@@ -0,0 +1,255 @@
+//! # Gyroflow Toolbox: Lens Profile Validation
+//!
+//! This module parses a lens profile with `gyroflow_core` and checks it for internal consistency, so that bad
+//! custom lens profiles are reported with an explanation, rather than just producing a garbage render.
+
+//---------------------------------------------------------
+// Local name bindings:
+//---------------------------------------------------------
+use gyroflow_core::lens_profile::LensProfile;
+
+use serde::Serialize;                       // Allows us to serialize the validation result
+
+//---------------------------------------------------------
+// The distortion models we can check, along with the
+// number of distortion coefficients they use:
+//---------------------------------------------------------
+const DISTORTION_MODEL_COEFFICIENTS: [(&str, usize); 5] = [
+    ("opencv_fisheye",  4),
+    ("opencv_standard", 5),
+    ("poly3",           1),
+    ("poly5",           2),
+    ("ptlens",          3),
+];
+
+/// The result of validating a lens profile.
+#[derive(Default, Serialize)]
+pub(crate) struct LensProfileValidation {
+    pub valid: bool,
+    pub identifier: String,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Parses & validates a lens profile.
+///
+/// # Arguments
+///
+/// * `json` - The lens profile JSON.
+///
+/// # Returns
+///
+/// The errors (which will stop the lens profile from working) and warnings (which might make the result less
+/// accurate) found in the lens profile.
+pub(crate) fn validate_lens_profile(json: &str) -> LensProfileValidation {
+    match LensProfile::from_json(json) {
+        Ok(profile) => validate_profile(&profile),
+        Err(e) => LensProfileValidation {
+            errors: vec![format!("The lens profile couldn't be parsed: {}", e)],
+            ..Default::default()
+        },
+    }
+}
+
+/// Validates a parsed lens profile.
+fn validate_profile(profile: &LensProfile) -> LensProfileValidation {
+    let mut validation = LensProfileValidation {
+        identifier: profile.identifier.clone(),
+        ..Default::default()
+    };
+
+    //---------------------------------------------------------
+    // Identifier & Names:
+    //---------------------------------------------------------
+    if profile.identifier.trim().is_empty() {
+        validation.errors.push("The lens profile has no identifier, so it can't be found in the lens profile database.".to_string());
+    }
+    if profile.camera_brand.trim().is_empty() || profile.camera_model.trim().is_empty() {
+        validation.warnings.push("The lens profile has no camera brand or model, so it won't be suggested for clips.".to_string());
+    }
+
+    //---------------------------------------------------------
+    // Dimensions:
+    //---------------------------------------------------------
+    let calib_width = profile.calib_dimension.w;
+    let calib_height = profile.calib_dimension.h;
+    if calib_width == 0 || calib_height == 0 {
+        validation.errors.push(format!("The calibration dimensions are {}x{}, but both must be greater than zero.", calib_width, calib_height));
+    }
+    if profile.orig_dimension.w == 0 || profile.orig_dimension.h == 0 {
+        validation.warnings.push(format!("The original video dimensions are {}x{}.", profile.orig_dimension.w, profile.orig_dimension.h));
+    }
+    if !is_positive(profile.fps) {
+        validation.warnings.push(format!("The frame rate is {}.", profile.fps));
+    }
+    if !is_positive(profile.input_horizontal_stretch) || !is_positive(profile.input_vertical_stretch) {
+        validation.errors.push(format!("The input stretch is {}x{}, but both must be greater than zero.", profile.input_horizontal_stretch, profile.input_vertical_stretch));
+    }
+
+    //---------------------------------------------------------
+    // Camera Matrix:
+    //---------------------------------------------------------
+    let camera_matrix = &profile.fisheye_params.camera_matrix;
+    if camera_matrix.len() != 3 {
+        validation.errors.push(format!("The camera matrix has {} rows, but must have 3.", camera_matrix.len()));
+    } else {
+        if camera_matrix.iter().flatten().any(|value| !value.is_finite()) {
+            validation.errors.push("The camera matrix contains NaN or infinite values.".to_string());
+        }
+
+        let (fx, fy, cx, cy) = (camera_matrix[0][0], camera_matrix[1][1], camera_matrix[0][2], camera_matrix[1][2]);
+        if !is_positive(fx) || !is_positive(fy) {
+            validation.errors.push(format!("The focal length is {}x{}, but both must be greater than zero.", fx, fy));
+        }
+        if calib_width > 0 && calib_height > 0 && (cx < 0.0 || cy < 0.0 || cx > calib_width as f64 || cy > calib_height as f64) {
+            validation.warnings.push(format!("The principal point ({}, {}) is outside of the calibration dimensions.", cx, cy));
+        }
+    }
+
+    //---------------------------------------------------------
+    // Distortion Model & Coefficients:
+    //---------------------------------------------------------
+    let coefficients = &profile.fisheye_params.distortion_coeffs;
+    if coefficients.iter().any(|value| !value.is_finite()) {
+        validation.errors.push("The distortion coefficients contain NaN or infinite values.".to_string());
+    }
+
+    let distortion_model = profile.distortion_model.as_deref().unwrap_or("opencv_fisheye");
+    match DISTORTION_MODEL_COEFFICIENTS.iter().find(|(model, _)| *model == distortion_model) {
+        Some((_, expected)) => {
+            if coefficients.len() < *expected {
+                validation.errors.push(format!("The \"{}\" distortion model needs {} distortion coefficients, but the lens profile has {}.", distortion_model, expected, coefficients.len()));
+            }
+        },
+        None => {
+            validation.warnings.push(format!("The \"{}\" distortion model isn't checked by the validator.", distortion_model));
+        }
+    }
+
+    validation.valid = validation.errors.is_empty();
+    validation
+}
+
+/// Whether or not a value is a finite number greater than zero.
+fn is_positive(value: f64) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_LENS_PROFILE: &str = r#"{
+        "name": "GoPro_HERO9 Black_Wide_16by9",
+        "calibrated_by": "Gyroflow Toolbox",
+        "camera_brand": "GoPro",
+        "camera_model": "HERO9 Black",
+        "lens_model": "Wide",
+        "camera_setting": "16:9",
+        "calib_dimension": { "w": 3840, "h": 2160 },
+        "orig_dimension": { "w": 3840, "h": 2160 },
+        "output_dimension": { "w": 3840, "h": 2160 },
+        "frame_readout_time": 15.3,
+        "input_horizontal_stretch": 1.0,
+        "input_vertical_stretch": 1.0,
+        "num_images": 20,
+        "fps": 29.97,
+        "use_opencv_fisheye": true,
+        "fisheye_params": {
+            "RMS_error": 0.5,
+            "camera_matrix": [
+                [ 1860.0, 0.0, 1920.0 ],
+                [ 0.0, 1860.0, 1080.0 ],
+                [ 0.0, 0.0, 1.0 ]
+            ],
+            "distortion_coeffs": [ 0.05, 0.01, -0.01, 0.002 ]
+        },
+        "identifier": "gopro_hero9_black_wide_16by9",
+        "calibrator_version": "1.5.4",
+        "date": "2026-01-01",
+        "global_shutter": false
+    }"#;
+
+    fn sample_profile() -> LensProfile {
+        LensProfile::from_json(SAMPLE_LENS_PROFILE).unwrap()
+    }
+
+    #[test]
+    fn sample_profile_is_valid() {
+        let validation = validate_lens_profile(SAMPLE_LENS_PROFILE);
+        assert!(validation.valid, "errors: {:?}", validation.errors);
+        assert_eq!(validation.identifier, "gopro_hero9_black_wide_16by9");
+        assert!(validation.warnings.is_empty(), "warnings: {:?}", validation.warnings);
+    }
+
+    #[test]
+    fn invalid_json_is_reported() {
+        let validation = validate_lens_profile("{ not json");
+        assert!(!validation.valid);
+        assert_eq!(validation.errors.len(), 1);
+    }
+
+    #[test]
+    fn nan_distortion_coefficients_are_errors() {
+        let mut profile = sample_profile();
+        profile.fisheye_params.distortion_coeffs[1] = f64::NAN;
+        let validation = validate_profile(&profile);
+        assert!(!validation.valid);
+        assert!(validation.errors.iter().any(|error| error.contains("distortion coefficients contain NaN")));
+    }
+
+    #[test]
+    fn nan_camera_matrix_values_are_errors() {
+        let mut profile = sample_profile();
+        profile.fisheye_params.camera_matrix[0][0] = f64::NAN;
+        let validation = validate_profile(&profile);
+        assert!(!validation.valid);
+        assert!(validation.errors.iter().any(|error| error.contains("camera matrix contains NaN")));
+        assert!(validation.errors.iter().any(|error| error.contains("focal length")));
+    }
+
+    #[test]
+    fn zero_calibration_dimensions_are_errors() {
+        let mut profile = sample_profile();
+        profile.calib_dimension.w = 0;
+        let validation = validate_profile(&profile);
+        assert!(!validation.valid);
+        assert!(validation.errors.iter().any(|error| error.contains("calibration dimensions are 0x2160")));
+    }
+
+    #[test]
+    fn zero_original_dimensions_are_warnings() {
+        let mut profile = sample_profile();
+        profile.orig_dimension.h = 0;
+        let validation = validate_profile(&profile);
+        assert!(validation.valid);
+        assert!(validation.warnings.iter().any(|warning| warning.contains("original video dimensions")));
+    }
+
+    #[test]
+    fn a_missing_identifier_is_an_error() {
+        let mut profile = sample_profile();
+        profile.identifier = String::new();
+        let validation = validate_profile(&profile);
+        assert!(!validation.valid);
+        assert!(validation.errors.iter().any(|error| error.contains("no identifier")));
+    }
+
+    #[test]
+    fn too_few_coefficients_for_the_distortion_model_are_errors() {
+        let mut profile = sample_profile();
+        profile.distortion_model = Some("opencv_standard".to_string());
+        let validation = validate_profile(&profile);
+        assert!(!validation.valid);
+        assert!(validation.errors.iter().any(|error| error.contains("needs 5 distortion coefficients, but the lens profile has 4")));
+    }
+
+    #[test]
+    fn unknown_distortion_models_are_warnings() {
+        let mut profile = sample_profile();
+        profile.distortion_model = Some("future_model".to_string());
+        let validation = validate_profile(&profile);
+        assert!(validation.valid);
+        assert!(validation.warnings.iter().any(|warning| warning.contains("\"future_model\"")));
+    }
+}
@@ -2,15 +2,29 @@
 //!
 //! This module allows for communication between the Gyroflow Toolbox Objective-C FxPlug4 code and the `gyroflow_core` Rust library.
 
+//---------------------------------------------------------
+// Modules:
+//---------------------------------------------------------
+mod adobe_lens_profiles;
+mod cache;
+#[cfg(feature = "calibration")]
+mod calibration;
+mod keyframes;
+mod lens_library;
+mod lens_profiles;
+mod lens_validation;
+mod media_import;
+mod redistort;
+
 //---------------------------------------------------------
 // Local name bindings:
 //---------------------------------------------------------
 use gyroflow_core::{StabilizationManager, stabilization::*};
 use gyroflow_core::gpu::{ BufferDescription, BufferSource, Buffers };
+use gyroflow_core::stabilization_params::ReadoutDirection;
 
+use cache::CachedManager;
 use once_cell::sync::OnceCell;              // Provides two new cell-like types, unsync::OnceCell and sync::OnceCell
-use lazy_static::*;                         // A macro for declaring lazily evaluated statics
-use lru::LruCache;                          // A LRU cache implementation
 use nalgebra::Vector4;                      // Allows us to use `Vector4`
 use std::ffi::CStr;                         // Allows us to use `CStr`
 use std::ffi::CString;                      // Allows us to use `CString`
@@ -42,34 +56,189 @@ pub extern "C" fn startLogger(
     if let Ok(file_log) = std::fs::File::create(log_path_string.as_ref()) {
         let _ = simplelog::WriteLogger::init(log::LevelFilter::Debug, log_config, file_log);
     }
+}
 
-    //---------------------------------------------------------
-    // Load the Lens Profiles:
-    //---------------------------------------------------------
-    let stab = StabilizationManager::default();
-    stab.lens_profile_db.write().load_all();
-    let mut lock = MANAGER_CACHE.lock().unwrap();
-    lock.put("lens-profiles".into(), Arc::new(stab));
+/// This function is called from Objective-C land to load all the lens profiles into the shared Lens Profile
+/// Library, if they haven't been loaded yet. The library is also loaded lazily the first time it's needed.
+///
+/// # Returns
+///
+/// This function returns 1 if the lens profiles are loaded, otherwise 0.
+#[unsafe(no_mangle)]
+pub extern "C" fn loadLensLibrary() -> std::ffi::c_int {
+    lens_library::load() as std::ffi::c_int
+}
+
+/// This function is called from Objective-C land to reload all the lens profiles from disk into the shared Lens
+/// Profile Library.
+///
+/// # Returns
+///
+/// This function returns 1 if the lens profiles are loaded, otherwise 0.
+#[unsafe(no_mangle)]
+pub extern "C" fn reloadLensLibrary() -> std::ffi::c_int {
+    lens_library::reload() as std::ffi::c_int
+}
+
+/// This function is called from Objective-C land to register a user lens profile directory (searched recursively
+/// for `.json` files) or a single lens profile file. The lens profiles are merged into the Lens Profile Library,
+/// replacing any bundled lens profiles with the same identifier, and are loaded again whenever the library is
+/// reloaded.
+///
+/// # Arguments
+///
+/// * `path` - A pointer to a C-style string containing the path to the directory or file.
+///
+/// # Returns
+///
+/// A pointer to a C-style string containing JSON with the `loaded` files, the `overridden` bundled identifiers, and the files that `failed` to load (each with a `path` and `error`).
+#[unsafe(no_mangle)]
+pub extern "C" fn addUserLensProfilePath(
+    path: *const c_char,
+) -> *const c_char {
+    let path_pointer = unsafe { CStr::from_ptr(path) };
+    let path_string = path_pointer.to_string_lossy();
+
+    let report = lens_library::add_user_path(std::path::Path::new(path_string.as_ref()));
+
+    let result = CString::new(serde_json::to_string(&report).unwrap_or_default()).unwrap();
+    return result.into_raw()
+}
+
+/// This function is called from Objective-C land to remove all the registered user lens profile paths, and reload
+/// the bundled lens profiles.
+///
+/// # Returns
+///
+/// This function returns 1 if the lens profiles are loaded, otherwise 0.
+#[unsafe(no_mangle)]
+pub extern "C" fn clearUserLensProfilePaths() -> std::ffi::c_int {
+    lens_library::clear_user_paths() as std::ffi::c_int
+}
+
+/// This function is called from Objective-C land to list the lens profiles in the Lens Profile Library.
+///
+/// # Arguments
+///
+/// * `filter_json` - A pointer to a C-style string containing the filter as JSON (i.e. `{"brand": "GoPro", "official_only": true}`), or an empty string for all lens profiles.
+///
+/// # Returns
+///
+/// A pointer to a C-style string containing a JSON array of lens profiles (identifier, name, brand, model, lens, setting, width, height, fps, rating, official), or a JSON object with an `error` message if the filter is invalid.
+#[unsafe(no_mangle)]
+pub extern "C" fn getLensProfiles(
+    filter_json: *const c_char,
+) -> *const c_char {
+    let filter_json_pointer = unsafe { CStr::from_ptr(filter_json) };
+    let filter_json_string = filter_json_pointer.to_string_lossy();
+
+    let result_string = match lens_profiles::LensProfileFilter::from_json(&filter_json_string) {
+        Ok(filter) => {
+            let db = lens_library::lens_profile_db();
+            let profiles = lens_profiles::filtered_profiles(&db.read(), &filter);
+            serde_json::to_string(&profiles).unwrap_or_default()
+        },
+        Err(e) => {
+            log::error!("[Gyroflow Toolbox Rust] Invalid lens profile filter: {:?}", e);
+            serde_json::json!({ "error": e }).to_string()
+        },
+    };
+
+    let result = CString::new(result_string).unwrap();
+    return result.into_raw()
+}
+
+/// This function is called from Objective-C land to fuzzy search the lens profiles in the Lens Profile Library.
+///
+/// # Arguments
+///
+/// * `query` - A pointer to a C-style string containing the search text (i.e. "gopro hero 11 wide").
+/// * `filter_json` - A pointer to a C-style string containing the filter as JSON, or an empty string for no filter.
+/// * `max_results` - The maximum number of results, or 0 for no limit.
+///
+/// # Returns
+///
+/// A pointer to a C-style string containing a JSON array of matching lens profiles, each with a `score` from 0 to 1, sorted from best to worst match, or a JSON object with an `error` message if the filter is invalid.
+#[unsafe(no_mangle)]
+pub extern "C" fn searchLensProfiles(
+    query: *const c_char,
+    filter_json: *const c_char,
+    max_results: u32,
+) -> *const c_char {
+    let query_pointer = unsafe { CStr::from_ptr(query) };
+    let query_string = query_pointer.to_string_lossy();
+
+    let filter_json_pointer = unsafe { CStr::from_ptr(filter_json) };
+    let filter_json_string = filter_json_pointer.to_string_lossy();
+
+    let result_string = match lens_profiles::LensProfileFilter::from_json(&filter_json_string) {
+        Ok(filter) => {
+            let db = lens_library::lens_profile_db();
+            let matches = lens_profiles::search_profiles(&db.read(), &query_string, &filter, max_results as usize);
+            serde_json::to_string(&matches).unwrap_or_default()
+        },
+        Err(e) => {
+            log::error!("[Gyroflow Toolbox Rust] Invalid lens profile filter: {:?}", e);
+            serde_json::json!({ "error": e }).to_string()
+        },
+    };
+
+    let result = CString::new(result_string).unwrap();
+    return result.into_raw()
+}
+
+/// Converts a rolling shutter readout direction index (as used by the FxPlug4 parameters) to a `ReadoutDirection`.
+///
+/// Returns `None` for any value outside of `0..=3`, which means "use the value from the Gyroflow Project".
+fn readout_direction_from_index(index: u8) -> Option<ReadoutDirection> {
+    match index {
+        0 => Some(ReadoutDirection::TopToBottom),
+        1 => Some(ReadoutDirection::BottomToTop),
+        2 => Some(ReadoutDirection::LeftToRight),
+        3 => Some(ReadoutDirection::RightToLeft),
+        _ => None,
+    }
+}
+
+/// Converts a `ReadoutDirection` to the index used by the FxPlug4 parameters.
+fn readout_direction_to_index(direction: ReadoutDirection) -> u8 {
+    match direction {
+        ReadoutDirection::TopToBottom => 0,
+        ReadoutDirection::BottomToTop => 1,
+        ReadoutDirection::LeftToRight => 2,
+        ReadoutDirection::RightToLeft => 3,
+    }
 }
 
-// This code block defines a lazy static variable called `MANAGER_CACHE` that is a `Mutex`-protected LRU cache of `StabilizationManager` instances.
-//
-// The `lazy_static!` macro is used to ensure that the variable is initialized only once, and only when it is first accessed.
-//
-// The `Mutex` is used to ensure that the cache can be safely accessed from multiple threads.
-//
-// The `LruCache` is used to limit the size of the cache to 8 items.
-//
-// # Example
-//
-// ```rust
-// use gyroflow::MANAGER_CACHE;
-//
-// let cache = MANAGER_CACHE.lock().unwrap();
-// let manager = cache.get("my_pixel_format").unwrap();
-// ```
-lazy_static! {
-    static ref MANAGER_CACHE: Mutex<LruCache<String, Arc<StabilizationManager>>> = Mutex::new(LruCache::new(std::num::NonZeroUsize::new(8).unwrap()));
+//---------------------------------------------------------
+// Adaptive Zoom Modes (as used by the FxPlug4 parameters):
+//---------------------------------------------------------
+const ADAPTIVE_ZOOM_MODE_DISABLED: u8 = 0;
+const ADAPTIVE_ZOOM_MODE_STATIC: u8 = 1;
+const ADAPTIVE_ZOOM_MODE_DYNAMIC: u8 = 2;
+
+//---------------------------------------------------------
+// The Gyroflow default adaptive zoom window (in seconds):
+//---------------------------------------------------------
+const DEFAULT_ADAPTIVE_ZOOM_WINDOW: f64 = 4.0;
+
+//---------------------------------------------------------
+// The Gyroflow default light refraction coefficient (air):
+//---------------------------------------------------------
+const DEFAULT_LIGHT_REFRACTION_COEFFICIENT: f64 = 1.0;
+
+/// Determines the adaptive zoom mode from a `gyroflow_core` adaptive zoom window.
+///
+/// `gyroflow_core` uses a negative window to disable zooming, zero for a static crop, and a positive value for the
+/// length (in seconds) of the dynamic zoom window.
+fn adaptive_zoom_mode_from_window(adaptive_zoom_window: f64) -> u8 {
+    if adaptive_zoom_window < -0.9 {
+        ADAPTIVE_ZOOM_MODE_DISABLED
+    } else if adaptive_zoom_window > 0.0001 {
+        ADAPTIVE_ZOOM_MODE_DYNAMIC
+    } else {
+        ADAPTIVE_ZOOM_MODE_STATIC
+    }
 }
 
 /// This function retrieves default values from a Gyroflow Project.
@@ -85,6 +254,18 @@ lazy_static! {
 /// * `position_offset_x` - A pointer to the position offset x value.
 /// * `position_offset_y` - A pointer to the position offset y value.
 /// * `video_rotation` - A pointer to the video rotation value.
+/// * `frame_readout_time` - A pointer to the rolling shutter frame readout time value (in milliseconds).
+/// * `frame_readout_direction` - A pointer to the rolling shutter frame readout direction index.
+/// * `adaptive_zoom_mode` - A pointer to the adaptive zoom mode (0 = Disabled, 1 = Static, 2 = Dynamic).
+/// * `adaptive_zoom_window` - A pointer to the dynamic adaptive zoom window value (in seconds).
+/// * `max_zoom` - A pointer to the maximum zoom value (in percent), or 0 if there is no limit.
+/// * `light_refraction_coefficient` - A pointer to the light refraction coefficient value.
+/// * `additional_rotation_pitch` - A pointer to the additional pitch rotation value (in degrees).
+/// * `additional_rotation_yaw` - A pointer to the additional yaw rotation value (in degrees).
+/// * `additional_rotation_roll` - A pointer to the additional roll rotation value (in degrees).
+/// * `additional_translation_x` - A pointer to the additional x translation value.
+/// * `additional_translation_y` - A pointer to the additional y translation value.
+/// * `additional_translation_z` - A pointer to the additional z translation value.
 ///
 /// # Safety
 ///
@@ -109,6 +290,18 @@ lazy_static! {
 /// let position_offset_x: *mut f64 = std::ptr::null_mut();
 /// let position_offset_y: *mut f64 = std::ptr::null_mut();
 /// let video_rotation: *mut f64 = std::ptr::null_mut();
+/// let frame_readout_time: *mut f64 = std::ptr::null_mut();
+/// let frame_readout_direction: *mut u8 = std::ptr::null_mut();
+/// let adaptive_zoom_mode: *mut u8 = std::ptr::null_mut();
+/// let adaptive_zoom_window: *mut f64 = std::ptr::null_mut();
+/// let max_zoom: *mut f64 = std::ptr::null_mut();
+/// let light_refraction_coefficient: *mut f64 = std::ptr::null_mut();
+/// let additional_rotation_pitch: *mut f64 = std::ptr::null_mut();
+/// let additional_rotation_yaw: *mut f64 = std::ptr::null_mut();
+/// let additional_rotation_roll: *mut f64 = std::ptr::null_mut();
+/// let additional_translation_x: *mut f64 = std::ptr::null_mut();
+/// let additional_translation_y: *mut f64 = std::ptr::null_mut();
+/// let additional_translation_z: *mut f64 = std::ptr::null_mut();
 ///
 /// let result = unsafe {
 ///     getDefaultValues(
@@ -121,6 +314,18 @@ lazy_static! {
 ///         position_offset_x,
 ///         position_offset_y,
 ///         video_rotation,
+///         frame_readout_time,
+///         frame_readout_direction,
+///         adaptive_zoom_mode,
+///         adaptive_zoom_window,
+///         max_zoom,
+///         light_refraction_coefficient,
+///         additional_rotation_pitch,
+///         additional_rotation_yaw,
+///         additional_rotation_roll,
+///         additional_translation_x,
+///         additional_translation_y,
+///         additional_translation_z,
 ///     )
 /// };
 ///
@@ -137,6 +342,18 @@ pub extern "C" fn getDefaultValues(
     position_offset_x: *mut f64,
     position_offset_y: *mut f64,
     video_rotation: *mut f64,
+    frame_readout_time: *mut f64,
+    frame_readout_direction: *mut u8,
+    adaptive_zoom_mode: *mut u8,
+    adaptive_zoom_window: *mut f64,
+    max_zoom: *mut f64,
+    light_refraction_coefficient: *mut f64,
+    additional_rotation_pitch: *mut f64,
+    additional_rotation_yaw: *mut f64,
+    additional_rotation_roll: *mut f64,
+    additional_translation_x: *mut f64,
+    additional_translation_y: *mut f64,
+    additional_translation_z: *mut f64,
 ) -> *const c_char {
     //---------------------------------------------------------
     // Convert the Gyroflow Project data to a `&str`:
@@ -145,18 +362,11 @@ pub extern "C" fn getDefaultValues(
     let gyroflow_project_data_string = gyroflow_project_data_pointer.to_string_lossy();
 
     let mut stab = StabilizationManager::default();
-    {
-        //---------------------------------------------------------
-        // Find first lens profile database with loaded profiles:
-        //---------------------------------------------------------
-        let lock = MANAGER_CACHE.lock().unwrap();
-        for (_, v) in lock.iter() {
-            if v.lens_profile_db.read().loaded {
-                stab.lens_profile_db = v.lens_profile_db.clone();
-                break;
-            }
-        }
-    }
+
+    //---------------------------------------------------------
+    // Use the shared Lens Profile Library:
+    //---------------------------------------------------------
+    lens_library::use_lens_library(&mut stab);
 
     //---------------------------------------------------------
     // Import the `gyroflow_project_data_string`:
@@ -187,6 +397,18 @@ pub extern "C" fn getDefaultValues(
                 *position_offset_x = params.adaptive_zoom_center_offset.0;
                 *position_offset_y = params.adaptive_zoom_center_offset.1;
                 *video_rotation = params.video_rotation;
+                *frame_readout_time = params.frame_readout_time;
+                *frame_readout_direction = readout_direction_to_index(params.frame_readout_direction);
+                *adaptive_zoom_mode = adaptive_zoom_mode_from_window(params.adaptive_zoom_window);
+                *adaptive_zoom_window = if params.adaptive_zoom_window > 0.0 { params.adaptive_zoom_window } else { DEFAULT_ADAPTIVE_ZOOM_WINDOW };
+                *max_zoom = params.max_zoom.unwrap_or(0.0);
+                *light_refraction_coefficient = params.light_refraction_coefficient;
+                *additional_rotation_pitch = params.additional_rotation.0;
+                *additional_rotation_yaw = params.additional_rotation.1;
+                *additional_rotation_roll = params.additional_rotation.2;
+                *additional_translation_x = params.additional_translation.0;
+                *additional_translation_y = params.additional_translation.1;
+                *additional_translation_z = params.additional_translation.2;
             }
 
             let result = CString::new("OK").unwrap();
@@ -229,18 +451,11 @@ pub extern "C" fn getLensIdentifier(
     let gyroflow_project_data_string = gyroflow_project_data_pointer.to_string_lossy();
 
     let mut stab = StabilizationManager::default();
-    {
-        //---------------------------------------------------------
-        // Find first lens profile database with loaded profiles:
-        //---------------------------------------------------------
-        let lock = MANAGER_CACHE.lock().unwrap();
-        for (_, v) in lock.iter() {
-            if v.lens_profile_db.read().loaded {
-                stab.lens_profile_db = v.lens_profile_db.clone();
-                break;
-            }
-        }
-    }
+
+    //---------------------------------------------------------
+    // Use the shared Lens Profile Library:
+    //---------------------------------------------------------
+    lens_library::use_lens_library(&mut stab);
 
     //---------------------------------------------------------
     // Import the `gyroflow_project_data_string`:
@@ -302,18 +517,11 @@ pub extern "C" fn isLensProfileLoaded(
     let gyroflow_project_data_string = gyroflow_project_data_pointer.to_string_lossy();
 
     let mut stab = StabilizationManager::default();
-    {
-        //---------------------------------------------------------
-        // Find first lens profile database with loaded profiles:
-        //---------------------------------------------------------
-        let lock = MANAGER_CACHE.lock().unwrap();
-        for (_, v) in lock.iter() {
-            if v.lens_profile_db.read().loaded {
-                stab.lens_profile_db = v.lens_profile_db.clone();
-                break;
-            }
-        }
-    }
+
+    //---------------------------------------------------------
+    // Use the shared Lens Profile Library:
+    //---------------------------------------------------------
+    lens_library::use_lens_library(&mut stab);
 
     //---------------------------------------------------------
     // Import the `gyroflow_project_data_string`:
@@ -380,18 +588,11 @@ pub extern "C" fn doesGyroflowProjectContainStabilisationData(
     let gyroflow_project_data_string = gyroflow_project_data_pointer.to_string_lossy();
 
     let mut stab: StabilizationManager = StabilizationManager::default();
-    {
-        //---------------------------------------------------------
-        // Find first lens profile database with loaded profiles:
-        //---------------------------------------------------------
-        let lock = MANAGER_CACHE.lock().unwrap();
-        for (_, v) in lock.iter() {
-            if v.lens_profile_db.read().loaded {
-                stab.lens_profile_db = v.lens_profile_db.clone();
-                break;
-            }
-        }
-    }
+
+    //---------------------------------------------------------
+    // Use the shared Lens Profile Library:
+    //---------------------------------------------------------
+    lens_library::use_lens_library(&mut stab);
 
     //---------------------------------------------------------
     // Import the `gyroflow_project_data_string`:
@@ -477,18 +678,11 @@ pub extern "C" fn hasAccurateTimestamps(
     let gyroflow_project_data_string = gyroflow_project_data_pointer.to_string_lossy();
 
     let mut stab = StabilizationManager::default();
-    {
-        //---------------------------------------------------------
-        // Find first lens profile database with loaded profiles:
-        //---------------------------------------------------------
-        let lock = MANAGER_CACHE.lock().unwrap();
-        for (_, v) in lock.iter() {
-            if v.lens_profile_db.read().loaded {
-                stab.lens_profile_db = v.lens_profile_db.clone();
-                break;
-            }
-        }
-    }
+
+    //---------------------------------------------------------
+    // Use the shared Lens Profile Library:
+    //---------------------------------------------------------
+    lens_library::use_lens_library(&mut stab);
 
     //---------------------------------------------------------
     // Import the `gyroflow_project_data_string`:
@@ -566,18 +760,11 @@ pub extern "C" fn loadLensProfile(
     let lens_profile_path_string = lens_profile_path_pointer.to_string_lossy();
 
     let mut stab = StabilizationManager::default();
-    {
-        //---------------------------------------------------------
-        // Find first lens profile database with loaded profiles:
-        //---------------------------------------------------------
-        let lock = MANAGER_CACHE.lock().unwrap();
-        for (_, v) in lock.iter() {
-            if v.lens_profile_db.read().loaded {
-                stab.lens_profile_db = v.lens_profile_db.clone();
-                break;
-            }
-        }
-    }
+
+    //---------------------------------------------------------
+    // Use the shared Lens Profile Library:
+    //---------------------------------------------------------
+    lens_library::use_lens_library(&mut stab);
 
     //---------------------------------------------------------
     // Import the `gyroflow_project_data_string`:
@@ -640,191 +827,903 @@ pub extern "C" fn loadLensProfile(
     }
 }
 
-/// Load a Gyroflow Preset to a supplied Gyroflow Project.
+/// Validates a lens profile, checking it for internal consistency (i.e. zero calibration dimensions, NaN
+/// coefficients, a mismatched distortion model or a missing identifier).
+///
+/// As this doesn't need a Gyroflow Project, it can be used from the plugin as well as from batch tooling.
 ///
 /// # Arguments
 ///
-/// * `gyroflow_project_data` - A pointer to a C-style string representing the Gyroflow Project data.
-/// * `preset_path` - A pointer to a C-style string representing the Profile data.
+/// * `lens_profile` - A pointer to a C-style string containing either the path to a lens profile, or the lens profile JSON itself.
 ///
 /// # Returns
 ///
-/// A new Gyroflow Project or "FAIL".
+/// A pointer to a C-style string containing JSON with `valid`, the lens profile `identifier`, and lists of `errors` and `warnings`.
 #[unsafe(no_mangle)]
-pub extern "C" fn loadPreset(
-    gyroflow_project_data: *const c_char,
-    preset_path: *const c_char,
+pub extern "C" fn validateLensProfile(
+    lens_profile: *const c_char,
 ) -> *const c_char {
-    //---------------------------------------------------------
-    // Convert the Gyroflow Project data to a `&str`:
-    //---------------------------------------------------------
-    let gyroflow_project_data_pointer = unsafe { CStr::from_ptr(gyroflow_project_data) };
-    let gyroflow_project_data_string = gyroflow_project_data_pointer.to_string_lossy();
+    let lens_profile_pointer = unsafe { CStr::from_ptr(lens_profile) };
+    let lens_profile_string = lens_profile_pointer.to_string_lossy();
 
     //---------------------------------------------------------
-    // Convert the Lens Profile data to a `&str`:
+    // Read the lens profile from disk if we've got a path:
     //---------------------------------------------------------
-    let preset_path_pointer = unsafe { CStr::from_ptr(preset_path) };
-    let preset_path_string = preset_path_pointer.to_string_lossy();
-
-    let mut stab = StabilizationManager::default();
-    {
-        //---------------------------------------------------------
-        // Find first lens profile database with loaded profiles:
-        //---------------------------------------------------------
-        let lock = MANAGER_CACHE.lock().unwrap();
-        for (_, v) in lock.iter() {
-            if v.lens_profile_db.read().loaded {
-                stab.lens_profile_db = v.lens_profile_db.clone();
-                break;
-            }
+    let validation = if lens_profile_string.trim_start().starts_with('{') {
+        lens_validation::validate_lens_profile(&lens_profile_string)
+    } else {
+        match std::fs::read_to_string(lens_profile_string.as_ref()) {
+            Ok(json) => lens_validation::validate_lens_profile(&json),
+            Err(e) => lens_validation::LensProfileValidation {
+                errors: vec![format!("The lens profile couldn't be read: {}", e)],
+                ..Default::default()
+            },
         }
-    }
-
-    //---------------------------------------------------------
-    // Import the `gyroflow_project_data_string`:
-    //---------------------------------------------------------
-    let blocking = true;
-    let cancel_flag = Arc::new(AtomicBool::new(false));
-    let mut is_preset = false;
-    match stab.import_gyroflow_data(
-        gyroflow_project_data_string.as_bytes(),
-        blocking,
-        None,
-        |_|(),
-        cancel_flag,
-        &mut is_preset,
-        true
-    ) {
-        Ok(_) => {
-            //---------------------------------------------------------
-            // Load Preset:
-            //---------------------------------------------------------
-            let mut is_preset = false;
-            if let Err(e) = stab.import_gyroflow_data(preset_path_string.as_bytes(), true, None, |_|(), Arc::new(AtomicBool::new(false)), &mut is_preset, true) {
-                log::error!("[Gyroflow Toolbox Rust] Error loading Preset: {:?}", e);
-                let result = CString::new("FAIL").unwrap();
-                return result.into_raw()
-            }
-
-            //---------------------------------------------------------
-            // Export Gyroflow data:
-            //---------------------------------------------------------
-            let gyroflow_data: String;
-            match stab.export_gyroflow_data(gyroflow_core::GyroflowProjectType::WithGyroData, "{}", None) {
-                Ok(data) => {
-                    gyroflow_data = data;
-                    log::info!("[Gyroflow Toolbox Rust] Gyroflow data exported successfully");
-                },
-                Err(e) => {
-                    log::error!("[Gyroflow Toolbox Rust] An error occured: {:?}", e);
-                    gyroflow_data = "FAIL".to_string();
-                }
-            }
-
-            //---------------------------------------------------------
-            // Return Gyroflow Project data as string:
-            //---------------------------------------------------------
-            let result = CString::new(gyroflow_data).unwrap();
-            return result.into_raw()
-        },
-        Err(e) => {
-            //---------------------------------------------------------
-            // An error has occurred:
-            //---------------------------------------------------------
-            log::error!("[Gyroflow Toolbox Rust] Error importing Preset: {:?}", e);
+    };
 
-            let error_msg = format!("{}", e);
-            let result = CString::new(error_msg).unwrap();
-            return result.into_raw()
-        },
+    for error in &validation.errors {
+        log::error!("[Gyroflow Toolbox Rust] Lens Profile validation error: {}", error);
     }
+
+    let result = CString::new(serde_json::to_string(&validation).unwrap_or_default()).unwrap();
+    return result.into_raw()
 }
 
-/// This function is called from Objective-C land and is responsible for clearing the cache.
+/// This function is called from Objective-C land to convert an Adobe Lens Correction Profile (`.lcp`) into Gyroflow
+/// lens profiles. One lens profile is written for each focal length in the LCP file, and each can then be loaded
+/// with `loadLensProfile`.
+///
+/// # Arguments
+///
+/// * `lcp_path` - A pointer to a C-style string containing the path to the LCP file.
+/// * `output_directory` - A pointer to a C-style string containing the directory to write the lens profiles to.
+/// * `fallback_width` - The image width to use if the LCP file doesn't specify one (or 0).
+/// * `fallback_height` - The image height to use if the LCP file doesn't specify one (or 0).
+/// * `register_in_library` - Whether or not to also register the converted lens profiles in the Lens Profile Library.
 ///
 /// # Returns
 ///
-/// This function returns the size of the cache as a `u32`.
+/// A pointer to a C-style string containing JSON with the converted `profiles` (each with an `identifier`, `path`, `focal_length`, `distortion_model`, whether the conversion was `exact`, and its `warnings`), the camera profiles that were `skipped`, and any `errors`.
 #[unsafe(no_mangle)]
-pub extern "C" fn trashCache() -> u32 {
-    //---------------------------------------------------------
-    // Trash the Cache:
-    //---------------------------------------------------------
-    let mut cache = MANAGER_CACHE.lock().unwrap();
-    cache.clear();
+pub extern "C" fn importAdobeLensProfile(
+    lcp_path: *const c_char,
+    output_directory: *const c_char,
+    fallback_width: u32,
+    fallback_height: u32,
+    register_in_library: u8,
+) -> *const c_char {
+    let lcp_path_pointer = unsafe { CStr::from_ptr(lcp_path) };
+    let lcp_path_string = lcp_path_pointer.to_string_lossy();
+
+    let output_directory_pointer = unsafe { CStr::from_ptr(output_directory) };
+    let output_directory_string = output_directory_pointer.to_string_lossy();
+
+    let report = adobe_lens_profiles::convert_lcp_file(
+        std::path::Path::new(lcp_path_string.as_ref()),
+        std::path::Path::new(output_directory_string.as_ref()),
+        fallback_width as usize,
+        fallback_height as usize,
+    );
 
     //---------------------------------------------------------
-    // Return the Cache Size:
+    // Register the converted lens profiles:
     //---------------------------------------------------------
-    cache.len() as u32
+    if register_in_library != 0 {
+        for profile in &report.profiles {
+            lens_library::add_user_path(std::path::Path::new(&profile.path));
+        }
+    }
+
+    let result = CString::new(serde_json::to_string(&report).unwrap_or_default()).unwrap();
+    return result.into_raw()
 }
 
-/// The "Import Media File" function that gets triggered from Objective-C Land.
+/// This function is called from Objective-C land to start calibrating a lens from a series of chessboard frames.
+///
+/// Only available when built with the `calibration` feature.
 ///
 /// # Arguments
 ///
-/// * `media_file_path` - A pointer to a C-style string containing the path to the media file.
+/// * `calibration_identifier` - A pointer to a C-style string containing an identifier for the calibration.
+/// * `board_columns` - The number of inner corners across the chessboard.
+/// * `board_rows` - The number of inner corners down the chessboard.
+/// * `width` - The width of the frames.
+/// * `height` - The height of the frames.
 ///
 /// # Returns
 ///
-/// This function returns the Gyroflow Project as a string or "FAIL".
+/// A pointer to a C-style string containing "OK", or an error message.
+#[cfg(feature = "calibration")]
 #[unsafe(no_mangle)]
-pub extern "C" fn importMediaFile(
-    media_file_path: *const c_char,
+pub extern "C" fn startLensCalibration(
+    calibration_identifier: *const c_char,
+    board_columns: u32,
+    board_rows: u32,
+    width: u32,
+    height: u32,
 ) -> *const c_char {
-    //---------------------------------------------------------
-    // Convert the file path to a `&str`:
-    //---------------------------------------------------------
-    let media_file_path_pointer = unsafe { CStr::from_ptr(media_file_path) };
-    let media_file_path_string = media_file_path_pointer.to_string_lossy();
+    let calibration_identifier_pointer = unsafe { CStr::from_ptr(calibration_identifier) };
+    let calibration_identifier_string = calibration_identifier_pointer.to_string_lossy();
 
-    //log::info!("[Gyroflow Toolbox Rust] media_file_path_string: {:?}", media_file_path_string);
+    let result_string = match calibration::start(&calibration_identifier_string, board_columns as usize, board_rows as usize, width as usize, height as usize) {
+        Ok(_) => "OK".to_string(),
+        Err(e) => {
+            log::error!("[Gyroflow Toolbox Rust] Failed to start lens calibration: {}", e);
+            e
+        },
+    };
 
-    let mut stab = StabilizationManager::default();
-    {
-        //---------------------------------------------------------
-        // Find first lens profile database with loaded profiles:
-        //---------------------------------------------------------
-        let lock = MANAGER_CACHE.lock().unwrap();
-        for (_, v) in lock.iter() {
-            if v.lens_profile_db.read().loaded {
-                stab.lens_profile_db = v.lens_profile_db.clone();
-                break;
+    let result = CString::new(result_string).unwrap();
+    return result.into_raw()
+}
+
+/// This function is called from Objective-C land to add a CPU chessboard frame to a lens calibration.
+///
+/// Only available when built with the `calibration` feature.
+///
+/// # Arguments
+///
+/// * `calibration_identifier` - A pointer to a C-style string containing the identifier of the calibration.
+/// * `timestamp` - The timestamp of the frame in microseconds.
+/// * `pixels` - A pointer to the 8-bit pixels of the frame.
+/// * `bytes_per_row` - The number of bytes in each row of the frame.
+/// * `channels` - The number of channels in the frame, either 1 (grayscale) or 4 (BGRA/RGBA).
+///
+/// # Returns
+///
+/// This function returns 1 if the chessboard was found in the frame, 0 if it wasn't, or -1 if an error occurred.
+#[cfg(feature = "calibration")]
+#[unsafe(no_mangle)]
+pub extern "C" fn addLensCalibrationFrame(
+    calibration_identifier: *const c_char,
+    timestamp: i64,
+    pixels: *const u8,
+    bytes_per_row: u32,
+    height: u32,
+    channels: u32,
+) -> std::ffi::c_int {
+    let calibration_identifier_pointer = unsafe { CStr::from_ptr(calibration_identifier) };
+    let calibration_identifier_string = calibration_identifier_pointer.to_string_lossy();
+
+    if pixels.is_null() {
+        log::error!("[Gyroflow Toolbox Rust] Missing lens calibration frame pixels.");
+        return -1
+    }
+    let pixels_slice = unsafe { std::slice::from_raw_parts(pixels, bytes_per_row as usize * height as usize) };
+
+    match calibration::add_frame(&calibration_identifier_string, timestamp, pixels_slice, bytes_per_row as usize, channels as usize) {
+        Ok(found) => found as std::ffi::c_int,
+        Err(e) => {
+            log::error!("[Gyroflow Toolbox Rust] Failed to add lens calibration frame: {}", e);
+            -1
+        },
+    }
+}
+
+/// This function is called from Objective-C land to finish a lens calibration, and write out the lens profile.
+///
+/// Only available when built with the `calibration` feature.
+///
+/// # Arguments
+///
+/// * `calibration_identifier` - A pointer to a C-style string containing the identifier of the calibration.
+/// * `output_path` - A pointer to a C-style string containing the path to write the lens profile JSON to.
+/// * `camera_brand` - A pointer to a C-style string containing the camera brand.
+/// * `camera_model` - A pointer to a C-style string containing the camera model.
+/// * `lens_model` - A pointer to a C-style string containing the lens model.
+/// * `camera_setting` - A pointer to a C-style string containing the camera setting (i.e. "4K 16:9").
+/// * `fps` - The frame rate the lens was calibrated at.
+///
+/// # Returns
+///
+/// A pointer to a C-style string containing JSON with the `rms` error, the `coverage` (0 to 1), `frames_detected`, `frames_total`, the lens profile `identifier` and `lens_profile_path`, or an error message.
+#[cfg(feature = "calibration")]
+#[unsafe(no_mangle)]
+pub extern "C" fn finishLensCalibration(
+    calibration_identifier: *const c_char,
+    output_path: *const c_char,
+    camera_brand: *const c_char,
+    camera_model: *const c_char,
+    lens_model: *const c_char,
+    camera_setting: *const c_char,
+    fps: f64,
+) -> *const c_char {
+    let to_string = |pointer: *const c_char| unsafe { CStr::from_ptr(pointer) }.to_string_lossy().to_string();
+
+    let details = calibration::CalibrationDetails {
+        camera_brand: to_string(camera_brand),
+        camera_model: to_string(camera_model),
+        lens_model: to_string(lens_model),
+        camera_setting: to_string(camera_setting),
+        fps,
+    };
+
+    let result_string = match calibration::finish(&to_string(calibration_identifier), &to_string(output_path), details) {
+        Ok(calibration_result) => serde_json::to_string(&calibration_result).unwrap_or_default(),
+        Err(e) => {
+            log::error!("[Gyroflow Toolbox Rust] Failed to finish lens calibration: {}", e);
+            e
+        },
+    };
+
+    let result = CString::new(result_string).unwrap();
+    return result.into_raw()
+}
+
+/// This function is called from Objective-C land to cancel a lens calibration in progress.
+///
+/// Only available when built with the `calibration` feature.
+///
+/// # Arguments
+///
+/// * `calibration_identifier` - A pointer to a C-style string containing the identifier of the calibration.
+#[cfg(feature = "calibration")]
+#[unsafe(no_mangle)]
+pub extern "C" fn cancelLensCalibration(
+    calibration_identifier: *const c_char,
+) {
+    let calibration_identifier_pointer = unsafe { CStr::from_ptr(calibration_identifier) };
+    let calibration_identifier_string = calibration_identifier_pointer.to_string_lossy();
+
+    calibration::cancel(&calibration_identifier_string);
+}
+
+/// The result of `suggestLensProfile`.
+#[derive(serde::Serialize)]
+struct LensProfileSuggestions {
+    camera: lens_profiles::ClipMetadata,
+    suggestions: Vec<lens_profiles::LensProfileMatch>,
+    applied_identifier: Option<String>,
+    gyroflow_project: Option<String>,
+}
+
+/// Suggests lens profiles for a Gyroflow Project (i.e. one created by `importMediaFile`), using the camera brand,
+/// model, lens, resolution and frame rate that `gyroflow_core` detected.
+///
+/// # Arguments
+///
+/// * `gyroflow_project_data` - A pointer to a C-style string representing the Gyroflow Project data.
+/// * `max_results` - The maximum number of suggestions, or 0 for no limit.
+/// * `auto_apply_threshold` - If the best suggestion has a score (from 0 to 1) of at least this value, it's loaded into the Gyroflow Project. Use `NaN` to never auto-apply.
+///
+/// # Returns
+///
+/// A pointer to a C-style string containing JSON with the detected `camera`, the ranked `suggestions` (each with a `score`), and if a suggestion was applied, the `applied_identifier` and the new `gyroflow_project`. Otherwise an error message.
+#[unsafe(no_mangle)]
+pub extern "C" fn suggestLensProfile(
+    gyroflow_project_data: *const c_char,
+    max_results: u32,
+    auto_apply_threshold: f64,
+) -> *const c_char {
+    //---------------------------------------------------------
+    // Convert the Gyroflow Project data to a `&str`:
+    //---------------------------------------------------------
+    let gyroflow_project_data_pointer = unsafe { CStr::from_ptr(gyroflow_project_data) };
+    let gyroflow_project_data_string = gyroflow_project_data_pointer.to_string_lossy();
+
+    let mut stab = StabilizationManager::default();
+
+    //---------------------------------------------------------
+    // Use the shared Lens Profile Library:
+    //---------------------------------------------------------
+    lens_library::use_lens_library(&mut stab);
+
+    //---------------------------------------------------------
+    // Import the `gyroflow_project_data_string`:
+    //---------------------------------------------------------
+    let blocking = true;
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let mut is_preset = false;
+    if let Err(e) = stab.import_gyroflow_data(
+        gyroflow_project_data_string.as_bytes(),
+        blocking,
+        None,
+        |_|(),
+        cancel_flag,
+        &mut is_preset,
+        true
+    ) {
+        log::error!("[Gyroflow Toolbox Rust] Error importing gyroflow data: {:?}", e);
+
+        let error_msg = format!("{}", e);
+        let result = CString::new(error_msg).unwrap();
+        return result.into_raw()
+    }
+
+    //---------------------------------------------------------
+    // Rank the lens profiles:
+    //---------------------------------------------------------
+    let camera = lens_profiles::ClipMetadata::from_manager(&stab);
+    let suggestions = lens_profiles::suggest_profiles(&stab.lens_profile_db.read(), &camera, max_results as usize);
+
+    //---------------------------------------------------------
+    // Apply the best suggestion, if we're confident enough:
+    //---------------------------------------------------------
+    let mut applied_identifier = None;
+    let mut gyroflow_project = None;
+    if let Some(best) = lens_profiles::auto_apply_suggestion(&suggestions, auto_apply_threshold) {
+        match stab.load_lens_profile(&best.profile.identifier) {
+            Ok(_) => {
+                match stab.export_gyroflow_data(gyroflow_core::GyroflowProjectType::WithGyroData, "{}", None) {
+                    Ok(data) => {
+                        log::info!("[Gyroflow Toolbox Rust] Automatically applied Lens Profile: {}", best.profile.identifier);
+                        applied_identifier = Some(best.profile.identifier.clone());
+                        gyroflow_project = Some(data);
+                    },
+                    Err(e) => {
+                        log::error!("[Gyroflow Toolbox Rust] An error occured: {:?}", e);
+                    }
+                }
+            },
+            Err(e) => {
+                log::error!("[Gyroflow Toolbox Rust] Error loading Lens Profile: {:?}", e);
             }
         }
     }
 
     //---------------------------------------------------------
-    // Load video file:
+    // Return the suggestions as JSON:
+    //---------------------------------------------------------
+    let result = LensProfileSuggestions {
+        camera,
+        suggestions,
+        applied_identifier,
+        gyroflow_project,
+    };
+    let result = CString::new(serde_json::to_string(&result).unwrap_or_default()).unwrap();
+    return result.into_raw()
+}
+
+/// Load a Gyroflow Preset to a supplied Gyroflow Project.
+///
+/// # Arguments
+///
+/// * `gyroflow_project_data` - A pointer to a C-style string representing the Gyroflow Project data.
+/// * `preset_path` - A pointer to a C-style string representing the Profile data.
+///
+/// # Returns
+///
+/// A new Gyroflow Project or "FAIL".
+#[unsafe(no_mangle)]
+pub extern "C" fn loadPreset(
+    gyroflow_project_data: *const c_char,
+    preset_path: *const c_char,
+) -> *const c_char {
+    //---------------------------------------------------------
+    // Convert the Gyroflow Project data to a `&str`:
+    //---------------------------------------------------------
+    let gyroflow_project_data_pointer = unsafe { CStr::from_ptr(gyroflow_project_data) };
+    let gyroflow_project_data_string = gyroflow_project_data_pointer.to_string_lossy();
+
+    //---------------------------------------------------------
+    // Convert the Lens Profile data to a `&str`:
+    //---------------------------------------------------------
+    let preset_path_pointer = unsafe { CStr::from_ptr(preset_path) };
+    let preset_path_string = preset_path_pointer.to_string_lossy();
+
+    let mut stab = StabilizationManager::default();
+
+    //---------------------------------------------------------
+    // Use the shared Lens Profile Library:
+    //---------------------------------------------------------
+    lens_library::use_lens_library(&mut stab);
+
+    //---------------------------------------------------------
+    // Import the `gyroflow_project_data_string`:
+    //---------------------------------------------------------
+    let blocking = true;
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let mut is_preset = false;
+    match stab.import_gyroflow_data(
+        gyroflow_project_data_string.as_bytes(),
+        blocking,
+        None,
+        |_|(),
+        cancel_flag,
+        &mut is_preset,
+        true
+    ) {
+        Ok(_) => {
+            //---------------------------------------------------------
+            // Load Preset:
+            //---------------------------------------------------------
+            let mut is_preset = false;
+            if let Err(e) = stab.import_gyroflow_data(preset_path_string.as_bytes(), true, None, |_|(), Arc::new(AtomicBool::new(false)), &mut is_preset, true) {
+                log::error!("[Gyroflow Toolbox Rust] Error loading Preset: {:?}", e);
+                let result = CString::new("FAIL").unwrap();
+                return result.into_raw()
+            }
+
+            //---------------------------------------------------------
+            // Export Gyroflow data:
+            //---------------------------------------------------------
+            let gyroflow_data: String;
+            match stab.export_gyroflow_data(gyroflow_core::GyroflowProjectType::WithGyroData, "{}", None) {
+                Ok(data) => {
+                    gyroflow_data = data;
+                    log::info!("[Gyroflow Toolbox Rust] Gyroflow data exported successfully");
+                },
+                Err(e) => {
+                    log::error!("[Gyroflow Toolbox Rust] An error occured: {:?}", e);
+                    gyroflow_data = "FAIL".to_string();
+                }
+            }
+
+            //---------------------------------------------------------
+            // Return Gyroflow Project data as string:
+            //---------------------------------------------------------
+            let result = CString::new(gyroflow_data).unwrap();
+            return result.into_raw()
+        },
+        Err(e) => {
+            //---------------------------------------------------------
+            // An error has occurred:
+            //---------------------------------------------------------
+            log::error!("[Gyroflow Toolbox Rust] Error importing Preset: {:?}", e);
+
+            let error_msg = format!("{}", e);
+            let result = CString::new(error_msg).unwrap();
+            return result.into_raw()
+        },
+    }
+}
+
+/// Sets the additional rotation and translation of a supplied Gyroflow Project, so that they're included when
+/// the Gyroflow Project is exported.
+///
+/// # Arguments
+///
+/// * `gyroflow_project_data` - A pointer to a C-style string representing the Gyroflow Project data.
+/// * `additional_rotation_pitch` - The additional pitch rotation in degrees.
+/// * `additional_rotation_yaw` - The additional yaw rotation in degrees.
+/// * `additional_rotation_roll` - The additional roll rotation in degrees.
+/// * `additional_translation_x` - The additional x translation.
+/// * `additional_translation_y` - The additional y translation.
+/// * `additional_translation_z` - The additional z translation.
+///
+/// # Returns
+///
+/// A new Gyroflow Project or "FAIL".
+#[unsafe(no_mangle)]
+pub extern "C" fn setAdditionalRotationAndTranslation(
+    gyroflow_project_data: *const c_char,
+    additional_rotation_pitch: f64,
+    additional_rotation_yaw: f64,
+    additional_rotation_roll: f64,
+    additional_translation_x: f64,
+    additional_translation_y: f64,
+    additional_translation_z: f64,
+) -> *const c_char {
+    //---------------------------------------------------------
+    // Convert the Gyroflow Project data to a `&str`:
+    //---------------------------------------------------------
+    let gyroflow_project_data_pointer = unsafe { CStr::from_ptr(gyroflow_project_data) };
+    let gyroflow_project_data_string = gyroflow_project_data_pointer.to_string_lossy();
+
+    let mut stab = StabilizationManager::default();
+
+    //---------------------------------------------------------
+    // Use the shared Lens Profile Library:
+    //---------------------------------------------------------
+    lens_library::use_lens_library(&mut stab);
+
+    //---------------------------------------------------------
+    // Import the `gyroflow_project_data_string`:
+    //---------------------------------------------------------
+    let blocking = true;
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let mut is_preset = false;
+    match stab.import_gyroflow_data(
+        gyroflow_project_data_string.as_bytes(),
+        blocking,
+        None,
+        |_|(),
+        cancel_flag,
+        &mut is_preset,
+        true
+    ) {
+        Ok(_) => {
+            //---------------------------------------------------------
+            // Set the Additional Rotation & Translation:
+            //---------------------------------------------------------
+            {
+                let mut params = stab.params.write();
+                params.additional_rotation = (additional_rotation_pitch, additional_rotation_yaw, additional_rotation_roll);
+                params.additional_translation = (additional_translation_x, additional_translation_y, additional_translation_z);
+            }
+
+            //---------------------------------------------------------
+            // Export Gyroflow data:
+            //---------------------------------------------------------
+            let gyroflow_data: String;
+            match stab.export_gyroflow_data(gyroflow_core::GyroflowProjectType::WithGyroData, "{}", None) {
+                Ok(data) => {
+                    gyroflow_data = data;
+                    log::info!("[Gyroflow Toolbox Rust] Gyroflow data exported successfully");
+                },
+                Err(e) => {
+                    log::error!("[Gyroflow Toolbox Rust] An error occured: {:?}", e);
+                    gyroflow_data = "FAIL".to_string();
+                }
+            }
+
+            //---------------------------------------------------------
+            // Return Gyroflow Project data as string:
+            //---------------------------------------------------------
+            let result = CString::new(gyroflow_data).unwrap();
+            return result.into_raw()
+        },
+        Err(e) => {
+            //---------------------------------------------------------
+            // An error has occurred:
+            //---------------------------------------------------------
+            log::error!("[Gyroflow Toolbox Rust] Error importing gyroflow data: {:?}", e);
+
+            let error_msg = format!("{}", e);
+            let result = CString::new(error_msg).unwrap();
+            return result.into_raw()
+        },
+    }
+}
+
+/// Sets the keyframe curves for a Gyroflow Toolbox instance.
+///
+/// The keyframes are pushed into the `gyroflow_core` keyframe manager the next time the instance is rendered, so
+/// that animated parameters are evaluated by `gyroflow_core` and the smoothing is only computed once for the
+/// whole clip. Supplying an empty object (`{}`) removes all keyframes.
+///
+/// # Arguments
+///
+/// * `unique_identifier` - A pointer to a C-style string containing the unique identifier of the instance.
+/// * `keyframes_json` - A pointer to a C-style string containing the keyframe curves as JSON, keyed by `processFrame`
+///   parameter name (`fov`, `smoothness`, `lens_correction`, `horizon_lock`, `horizon_roll`, `position_offset_x` or
+///   `position_offset_y`), i.e. `{ "smoothness": [ { "timestamp_us": 0, "value": 0.5, "easing": "EaseInOut" } ] }`.
+///
+/// # Returns
+///
+/// A pointer to a C-style string containing either "OK" or a failure string.
+#[unsafe(no_mangle)]
+pub extern "C" fn setKeyframes(
+    unique_identifier: *const c_char,
+    keyframes_json: *const c_char,
+) -> *const c_char {
+    let unique_identifier_pointer = unsafe { CStr::from_ptr(unique_identifier) };
+    let unique_identifier_string = unique_identifier_pointer.to_string_lossy();
+
+    let keyframes_json_pointer = unsafe { CStr::from_ptr(keyframes_json) };
+    let keyframes_json_string = keyframes_json_pointer.to_string_lossy();
+
+    match keyframes::parse_keyframe_curves(&keyframes_json_string) {
+        Ok(curves) => {
+            keyframes::set_keyframe_curves(&unique_identifier_string, curves);
+
+            let result = CString::new("OK").unwrap();
+            return result.into_raw()
+        },
+        Err(e) => {
+            log::error!("[Gyroflow Toolbox Rust] Error parsing keyframes: {:?}", e);
+
+            let result = CString::new(e).unwrap();
+            return result.into_raw()
+        },
+    }
+}
+
+/// Removes the keyframe curves of a Gyroflow Toolbox instance, for example when the instance is deleted.
+///
+/// Keyframe curves are host state, so they're not removed when the cache is trashed.
+///
+/// # Arguments
+///
+/// * `unique_identifier` - A pointer to a C-style string containing the unique identifier of the instance.
+#[unsafe(no_mangle)]
+pub extern "C" fn clearKeyframes(
+    unique_identifier: *const c_char,
+) {
+    let unique_identifier_pointer = unsafe { CStr::from_ptr(unique_identifier) };
+    let unique_identifier_string = unique_identifier_pointer.to_string_lossy();
+
+    keyframes::remove_keyframe_curves(&unique_identifier_string);
+}
+
+/// This function is called from Objective-C land and is responsible for clearing the cache.
+///
+/// The Lens Profile Library isn't stored in the cache, so it survives the purge. The keyframe curves are host
+/// state rather than cached data, so they're kept, and applied again when the instances are next rendered.
+///
+/// # Returns
+///
+/// This function returns the size of the cache as a `u32`.
+#[unsafe(no_mangle)]
+pub extern "C" fn trashCache() -> u32 {
+    //---------------------------------------------------------
+    // Trash the Cache, and return the Cache Size:
     //---------------------------------------------------------
-    match stab.load_video_file(&media_file_path_string, None, true) {
-        Ok(_) => {
-            log::info!("[Gyroflow Toolbox Rust] Video file loaded successfully");
+    cache::trash(|_| true) as u32
+}
+
+/// This function is called from Objective-C land to remove the cache entries of a single instance.
+///
+/// The keyframe curves of the instance are kept. Use `clearKeyframes` to remove them.
+///
+/// # Arguments
+///
+/// * `unique_identifier` - A pointer to a C-style string containing the unique identifier of the instance.
+///
+/// # Returns
+///
+/// This function returns the size of the cache as a `u32`.
+#[unsafe(no_mangle)]
+pub extern "C" fn trashCacheForUniqueIdentifier(
+    unique_identifier: *const c_char,
+) -> u32 {
+    let unique_identifier_pointer = unsafe { CStr::from_ptr(unique_identifier) };
+    let unique_identifier_string = unique_identifier_pointer.to_string_lossy();
+
+    cache::forget_project_hash(&unique_identifier_string);
+    cache::trash(|key| key.unique_identifier == unique_identifier_string) as u32
+}
+
+/// This function is called from Objective-C land to remove the cache entries of a Gyroflow Project path.
+///
+/// # Arguments
+///
+/// * `path` - A pointer to a C-style string containing the path to the Gyroflow Project.
+///
+/// # Returns
+///
+/// This function returns the size of the cache as a `u32`.
+#[unsafe(no_mangle)]
+pub extern "C" fn trashCacheForProjectPath(
+    path: *const c_char,
+) -> u32 {
+    let path_pointer = unsafe { CStr::from_ptr(path) };
+    let path_string = path_pointer.to_string_lossy();
+
+    cache::trash(|key| key.project_path == path_string) as u32
+}
+
+/// This function is called from Objective-C land to remove the cache entries that were imported from the
+/// supplied Gyroflow Project data (compared by a hash of the data).
+///
+/// # Arguments
+///
+/// * `gyroflow_project_data` - A pointer to a C-style string containing the Gyroflow Project data.
+///
+/// # Returns
+///
+/// This function returns the size of the cache as a `u32`.
+#[unsafe(no_mangle)]
+pub extern "C" fn trashCacheForProjectData(
+    gyroflow_project_data: *const c_char,
+) -> u32 {
+    let project_data = unsafe { CStr::from_ptr(gyroflow_project_data) }.to_bytes();
+    let project_hash = cache::project_hash(project_data);
+
+    cache::trash(|key| key.project_hash == Some(project_hash)) as u32
+}
+
+/// This function is called from Objective-C land to change the maximum number of entries in the cache.
+///
+/// # Arguments
+///
+/// * `capacity` - The maximum number of entries in the cache (at least 1).
+///
+/// # Returns
+///
+/// This function returns the size of the cache as a `u32`.
+#[unsafe(no_mangle)]
+pub extern "C" fn setCacheCapacity(
+    capacity: u32,
+) -> u32 {
+    cache::set_capacity(capacity as usize) as u32
+}
+
+/// This function is called from Objective-C land to change the memory budget of the cache. When the estimated
+/// size of the cached gyro data & GPU resources exceeds the budget, the least recently used entries are evicted.
+///
+/// # Arguments
+///
+/// * `memory_budget_mb` - The memory budget in megabytes, or 0 for no budget.
+///
+/// # Returns
+///
+/// This function returns the size of the cache as a `u32`.
+#[unsafe(no_mangle)]
+pub extern "C" fn setCacheMemoryBudget(
+    memory_budget_mb: u64,
+) -> u32 {
+    cache::set_memory_budget(memory_budget_mb.saturating_mul(1024 * 1024)) as u32
+}
+
+/// This function is called from Objective-C land to get the current cache entries, hits, misses and evictions,
+/// for support diagnostics.
+///
+/// # Returns
+///
+/// This function returns the cache statistics as a JSON string.
+#[unsafe(no_mangle)]
+pub extern "C" fn getCacheStatistics() -> *const c_char {
+    let result = CString::new(cache::statistics_json()).unwrap();
+    return result.into_raw()
+}
+
+/// The "Import Media File" function that gets triggered from Objective-C Land.
+///
+/// A Gyroflow Project saved next to the media file is used if there is one, otherwise the embedded telemetry, and
+/// then any telemetry sidecar files next to it (i.e. `.gcsv`, `.bbl`, Sony `.XML` or DJI `.SRT`).
+///
+/// # Arguments
+///
+/// * `media_file_path` - A pointer to a C-style string containing the path to the media file.
+///
+/// # Returns
+///
+/// A pointer to a C-style string containing JSON with the `result` (`success`, `cannot_open`, `no_telemetry`, `no_lens_profile` or `export_failed`), a `message`, the detected `camera` (brand, model, lens, width, height, fps), the `detected_source`, the `motion_data_file` that supplied the motion data, and the `gyroflow_project` (only when motion data was found).
+#[unsafe(no_mangle)]
+pub extern "C" fn importMediaFile(
+    media_file_path: *const c_char,
+) -> *const c_char {
+    //---------------------------------------------------------
+    // Convert the file path to a `&str`:
+    //---------------------------------------------------------
+    let media_file_path_pointer = unsafe { CStr::from_ptr(media_file_path) };
+    let media_file_path_string = media_file_path_pointer.to_string_lossy();
+
+    //log::info!("[Gyroflow Toolbox Rust] media_file_path_string: {:?}", media_file_path_string);
+
+    //---------------------------------------------------------
+    // Load video file (or its sidecars) & export the Gyroflow
+    // Project:
+    //---------------------------------------------------------
+    let import_result = media_import::import_media_file_result(&media_file_path_string);
+    match import_result.result {
+        media_import::MediaImportStatus::Success => {
+            log::info!("[Gyroflow Toolbox Rust] Motion data loaded successfully from: {:?}", import_result.motion_data_file);
         },
-        Err(e) => {
-            log::error!("[Gyroflow Toolbox Rust] An error occured: {:?}", e);
+        _ => {
+            log::error!("[Gyroflow Toolbox Rust] Media file import incomplete: {}", import_result.message);
         }
     }
 
     //---------------------------------------------------------
-    // Export Gyroflow data:
+    // Return the result as JSON:
     //---------------------------------------------------------
-    let gyroflow_data: String;
-    match stab.export_gyroflow_data(gyroflow_core::GyroflowProjectType::WithGyroData, "{}", None) {
+    let result = CString::new(serde_json::to_string(&import_result).unwrap_or_default()).unwrap();
+    return result.into_raw()
+}
+
+/// This function is called from Objective-C land to describe what a media file contains, before importing it.
+///
+/// # Arguments
+///
+/// * `media_file_path` - A pointer to a C-style string containing the path to the media file.
+///
+/// # Returns
+///
+/// A pointer to a C-style string containing JSON with the camera `brand`, `model` & `lens`, the `width`, `height`, `fps` and `duration_s`, whether it `has_gyro` & `has_accelerometer` data, the `imu_sample_rate` in Hz, the `detected_source` and the `detected_lens_profile`, or JSON with a `result` of `cannot_open` and an error `message` (as returned by `importMediaFile`).
+#[unsafe(no_mangle)]
+pub extern "C" fn probeMediaFile(
+    media_file_path: *const c_char,
+) -> *const c_char {
+    let media_file_path_pointer = unsafe { CStr::from_ptr(media_file_path) };
+    let media_file_path_string = media_file_path_pointer.to_string_lossy();
+
+    let result_string = match media_import::probe_media_file(&media_file_path_string) {
+        Ok(probe) => serde_json::to_string(&probe).unwrap_or_default(),
+        Err(e) => {
+            log::error!("[Gyroflow Toolbox Rust] Failed to probe media file: {}", e);
+            serde_json::json!({ "result": media_import::MediaImportStatus::CannotOpen, "message": e }).to_string()
+        },
+    };
+
+    let result = CString::new(result_string).unwrap();
+    return result.into_raw()
+}
+
+/// This function is called from Objective-C land to merge the chapters of a split recording (i.e. GoPro & DJI split
+/// long recordings into 4GB files) into a single Gyroflow Project, so that the smoothing is continuous across the
+/// joins.
+///
+/// # Arguments
+///
+/// * `chapter_paths_json` - A pointer to a C-style string containing a JSON array of the chapter file paths, in recording order.
+///
+/// # Returns
+///
+/// A pointer to a C-style string containing JSON with the merged `gyroflow_project`, its total `duration_ms`, and the `chapters` (each with a `path`, `start_ms` and `duration_ms`), or "FAIL".
+#[unsafe(no_mangle)]
+pub extern "C" fn importChapteredMediaFiles(
+    chapter_paths_json: *const c_char,
+) -> *const c_char {
+    let chapter_paths_json_pointer = unsafe { CStr::from_ptr(chapter_paths_json) };
+    let chapter_paths_json_string = chapter_paths_json_pointer.to_string_lossy();
+
+    let merged = serde_json::from_str::<Vec<String>>(&chapter_paths_json_string)
+        .map_err(|e| format!("Invalid chapter list: {}", e))
+        .and_then(|chapter_paths| media_import::merge_chapters(&chapter_paths));
+
+    let result_string = match merged {
+        Ok(merged) => serde_json::to_string(&merged).unwrap_or_default(),
+        Err(e) => {
+            log::error!("[Gyroflow Toolbox Rust] Failed to merge chapters: {}", e);
+            "FAIL".to_string()
+        }
+    };
+
+    let result = CString::new(result_string).unwrap();
+    return result.into_raw()
+}
+
+/// This function is called from Objective-C land to find the sidecar files next to a media file, that could supply
+/// its motion data.
+///
+/// # Arguments
+///
+/// * `media_file_path` - A pointer to a C-style string containing the path to the media file.
+///
+/// # Returns
+///
+/// A pointer to a C-style string containing a JSON array of sidecar files (each with a `path` and `kind`, which is one of `gyroflow_project`, `gcsv`, `blackbox`, `sony_xml` or `dji_srt`), best first.
+#[unsafe(no_mangle)]
+pub extern "C" fn findMediaSidecars(
+    media_file_path: *const c_char,
+) -> *const c_char {
+    let media_file_path_pointer = unsafe { CStr::from_ptr(media_file_path) };
+    let media_file_path_string = media_file_path_pointer.to_string_lossy();
+
+    let sidecars = media_import::find_sidecars(std::path::Path::new(media_file_path_string.as_ref()));
+
+    let result = CString::new(serde_json::to_string(&sidecars).unwrap_or_default()).unwrap();
+    return result.into_raw()
+}
+
+/// The "Import Gyro Log" function that gets triggered from Objective-C Land, for footage where the motion data was
+/// recorded by a separate logger (i.e. `.gcsv`, Betaflight Blackbox `.bbl`/`.bfl`, or a camera sidecar file).
+///
+/// # Arguments
+///
+/// * `gyro_log_path` - A pointer to a C-style string containing the path to the gyro log.
+/// * `width` - The width of the video.
+/// * `height` - The height of the video.
+/// * `fps` - The frame rate of the video.
+/// * `duration` - The duration of the video in seconds.
+/// * `start_offset` - The offset between the video & the gyro log in milliseconds (or 0).
+///
+/// # Returns
+///
+/// This function returns the Gyroflow Project as a string or "FAIL".
+#[unsafe(no_mangle)]
+pub extern "C" fn importGyroLogFile(
+    gyro_log_path: *const c_char,
+    width: u32,
+    height: u32,
+    fps: f64,
+    duration: f64,
+    start_offset: f64,
+) -> *const c_char {
+    let gyro_log_path_pointer = unsafe { CStr::from_ptr(gyro_log_path) };
+    let gyro_log_path_string = gyro_log_path_pointer.to_string_lossy();
+
+    let metadata = media_import::HostVideoMetadata {
+        width: width as usize,
+        height: height as usize,
+        fps,
+        duration_s: duration,
+        start_offset_ms: start_offset,
+    };
+
+    let gyroflow_data = match media_import::import_gyro_log(&gyro_log_path_string, &metadata) {
         Ok(data) => {
-            gyroflow_data = data;
             log::info!("[Gyroflow Toolbox Rust] Gyroflow data exported successfully");
+            data
         },
         Err(e) => {
-            log::error!("[Gyroflow Toolbox Rust] An error occured: {:?}", e);
-            gyroflow_data = "FAIL".to_string();
+            log::error!("[Gyroflow Toolbox Rust] Failed to import gyro log: {}", e);
+            "FAIL".to_string()
         }
-    }
+    };
 
-    //---------------------------------------------------------
-    // Return Gyroflow Project data as string:
-    //---------------------------------------------------------
     let result = CString::new(gyroflow_data).unwrap();
     return result.into_raw()
 }
@@ -852,6 +1751,19 @@ pub extern "C" fn importMediaFile(
 /// * `video_rotation` - The video rotation of the video frame.
 /// * `fov_overview` - The field of view overview of the video frame.
 /// * `disable_gyroflow_stretch` - Whether or not to disable Gyroflow stretch.
+/// * `rolling_shutter_correction` - Whether or not to apply rolling shutter correction.
+/// * `frame_readout_time` - The rolling shutter frame readout time in milliseconds, or `NaN` to use the value from the Gyroflow Project.
+/// * `frame_readout_direction` - The rolling shutter frame readout direction (0 = Top to Bottom, 1 = Bottom to Top, 2 = Left to Right, 3 = Right to Left), or any other value to use the direction from the Gyroflow Project.
+/// * `adaptive_zoom_mode` - The adaptive zoom mode (0 = Disabled, 1 = Static, 2 = Dynamic), or any other value to use the mode from the Gyroflow Project.
+/// * `adaptive_zoom_window` - The dynamic adaptive zoom window in seconds, or `NaN` to use the value from the Gyroflow Project.
+/// * `max_zoom` - The maximum zoom in percent (0 for no limit), or `NaN` to use the value from the Gyroflow Project.
+/// * `light_refraction_coefficient` - The light refraction coefficient (i.e. 1.33 for footage shot through a flat port underwater), or `NaN` to use the value from the Gyroflow Project.
+/// * `additional_rotation_pitch` - The additional pitch rotation in degrees, or `NaN` to use the value from the Gyroflow Project.
+/// * `additional_rotation_yaw` - The additional yaw rotation in degrees, or `NaN` to use the value from the Gyroflow Project.
+/// * `additional_rotation_roll` - The additional roll rotation in degrees, or `NaN` to use the value from the Gyroflow Project.
+/// * `additional_translation_x` - The additional x translation, or `NaN` to use the value from the Gyroflow Project.
+/// * `additional_translation_y` - The additional y translation, or `NaN` to use the value from the Gyroflow Project.
+/// * `additional_translation_z` - The additional z translation, or `NaN` to use the value from the Gyroflow Project.
 /// * `in_mtl_tex` - A pointer to the input Metal texture.
 /// * `out_mtl_tex` - A pointer to the output Metal texture.
 /// * `command_queue` - A pointer to the Metal command queue.
@@ -880,6 +1792,19 @@ pub extern "C" fn processFrame(
     video_rotation: f64,
     fov_overview: u8,
     disable_gyroflow_stretch: u8,
+    rolling_shutter_correction: u8,
+    frame_readout_time: f64,
+    frame_readout_direction: u8,
+    adaptive_zoom_mode: u8,
+    adaptive_zoom_window: f64,
+    max_zoom: f64,
+    light_refraction_coefficient: f64,
+    additional_rotation_pitch: f64,
+    additional_rotation_yaw: f64,
+    additional_rotation_roll: f64,
+    additional_translation_x: f64,
+    additional_translation_y: f64,
+    additional_translation_z: f64,
     in_mtl_tex: *mut std::ffi::c_void,
     out_mtl_tex: *mut std::ffi::c_void,
     command_queue: *mut std::ffi::c_void,
@@ -900,6 +1825,7 @@ pub extern "C" fn processFrame(
     // Have parameters changed:
     //---------------------------------------------------------
     let mut params_changed = false;
+    let mut smoothing_changed = false;
     let mut rotation_changed = false;
 
     //---------------------------------------------------------
@@ -937,92 +1863,115 @@ pub extern "C" fn processFrame(
     let number_of_bytes_value: usize = number_of_bytes as usize;
 
    //---------------------------------------------------------
-   // Cache the manager:
+   // Cache the manager. The cache is only locked while we
+   // find the entry, and concurrent first-time imports of the
+   // same key wait for the first one to finish.
+   //
+   // The render size isn't part of the key, as the gyro data
+   // & smoothing are shared between all render sizes:
    //---------------------------------------------------------
-   let mut cache = MANAGER_CACHE.lock().unwrap();
-   let cache_key = format!("{path_string}{output_width}{output_height}{pixel_format_string}{disable_gyroflow_stretch}{unique_identifier_string}");
-   let manager = if let Some(manager) = cache.get(&cache_key) {
+   let project_data = unsafe { CStr::from_ptr(data) }.to_bytes();
+   let cache_key = cache::CacheKey::stabilisation(&unique_identifier_string, &path_string, project_data, disable_gyroflow_stretch != 0);
+   let cached_manager = cache::cache_entry(&cache_key).get_or_try_init(|| {
        //---------------------------------------------------------
-       // Already cached:
+       // Setup the Gyroflow Manager:
        //---------------------------------------------------------
-       manager.clone()
-   } else {
+       let manager = StabilizationManager::default();
+
        //---------------------------------------------------------
-       // On first load, always Invalidate & Recompute:
+       // Import the Gyroflow Data. If it fails, nothing is
+       // cached, so the next frame tries again:
        //---------------------------------------------------------
-       params_changed = true;
+       let mut is_preset = false;
+       if let Err(e) = manager.import_gyroflow_data(project_data, true, None, |_|(), Arc::new(AtomicBool::new(false)), &mut is_preset, true) {
+           log::error!("[Gyroflow Toolbox Rust] Failed to import Gyroflow File: {:?}", e);
+           return Err(())
+       }
 
        //---------------------------------------------------------
-       // Setup the Gyroflow Manager:
+       // Disable Gyroflow Stretch:
        //---------------------------------------------------------
-       let manager = StabilizationManager::default();
+       if disable_gyroflow_stretch != 0 {
+           // TODO: Do we need to expose this an an option?
+           manager.disable_lens_stretch(false);
+       }
+
+       Ok(Arc::new(CachedManager::new(manager)))
+   });
+   let cached_manager = match cached_manager {
+       Ok(cached_manager) => cached_manager.clone(),
+       Err(_) => return 0,
+   };
+   let project_values = cached_manager.project_values;
 
+   //---------------------------------------------------------
+   // Get the render state for this size:
+   //---------------------------------------------------------
+   let cached_render = cached_manager.render(output_width, output_height, &pixel_format_string, |manager| {
        //---------------------------------------------------------
-       // Import the Gyroflow Data:
+       // Set the Input Size:
        //---------------------------------------------------------
-       let data_slice: &[u8] = unsafe {
-           CStr::from_ptr(data).to_bytes()
-       };
-       let mut is_preset = false;
-       match manager.import_gyroflow_data(&data_slice, true, None, |_|(), Arc::new(AtomicBool::new(false)), &mut is_preset, true) {
-           Ok(_) => {
-                //---------------------------------------------------------
-                // Disable Gyroflow Stretch:
-                //---------------------------------------------------------
-                if disable_gyroflow_stretch != 0 {
-                    // TODO: Do we need to expose this an an option?
-                    manager.disable_lens_stretch(false);
-                }
+       manager.set_size(output_width, output_height);
 
-               //---------------------------------------------------------
-               // Set the Input Size:
-               //---------------------------------------------------------
-               manager.set_size(output_width, output_height);
-
-               //---------------------------------------------------------
-               // Set the Output Size:
-               //---------------------------------------------------------
-               manager.set_output_size(output_width, output_height);
-
-               //---------------------------------------------------------
-               // Invert the Frame Buffer:
-               //---------------------------------------------------------
-               manager.params.write().framebuffer_inverted = true;
-
-               //---------------------------------------------------------
-               // Set Stabilisation Settings:
-               //---------------------------------------------------------
-               {
-                    let mut stab = manager.stabilization.write();
-
-                    //---------------------------------------------------------
-                    // Set the Interpolation:
-                    //---------------------------------------------------------
-                    stab.interpolation = gyroflow_core::stabilization::Interpolation::Lanczos4;
-
-                    //---------------------------------------------------------
-                    // Share wpgu instances:
-                    //---------------------------------------------------------
-                    stab.share_wgpu_instances = true;
-               }
+       //---------------------------------------------------------
+       // Set the Output Size:
+       //---------------------------------------------------------
+       manager.set_output_size(output_width, output_height);
+
+       //---------------------------------------------------------
+       // Invert the Frame Buffer:
+       //---------------------------------------------------------
+       manager.params.write().framebuffer_inverted = true;
+
+       //---------------------------------------------------------
+       // Set Stabilisation Settings:
+       //---------------------------------------------------------
+       {
+            let mut stab = manager.stabilization.write();
+
+            //---------------------------------------------------------
+            // Set the Interpolation:
+            //---------------------------------------------------------
+            stab.interpolation = gyroflow_core::stabilization::Interpolation::Lanczos4;
+
+            //---------------------------------------------------------
+            // Share wpgu instances:
+            //---------------------------------------------------------
+            stab.share_wgpu_instances = true;
+       }
+
+       //---------------------------------------------------------
+       // Force the background color to transparent:
+       //---------------------------------------------------------
+       let background_color: Vector4<f32> = Vector4::new(0.0, 0.0, 0.0, 0.0);
+       manager.set_background_color(background_color);
+   });
+   //---------------------------------------------------------
+   // The host's parameters are applied to the requested
+   // manager, which is only rendered with once it has been
+   // recomputed:
+   //---------------------------------------------------------
+   let manager = cached_render.requested.clone();
+
+   //---------------------------------------------------------
+   // Lock this instance while we apply the parameters. On
+   // first load, always Invalidate & Recompute:
+   //---------------------------------------------------------
+   let mut instance_state = cached_render.instance_state.lock().unwrap();
+   let first_load = !instance_state.has_recomputed;
 
-               //---------------------------------------------------------
-               // Force the background color to transparent:
-               //---------------------------------------------------------
-               let background_color: Vector4<f32> = Vector4::new(0.0, 0.0, 0.0, 0.0);
-               manager.set_background_color(background_color);
-           },
-           Err(e) => {
-               //---------------------------------------------------------
-               // Return an error message is something fails:
-               //---------------------------------------------------------
-               log::error!("[Gyroflow Toolbox Rust] Failed to import Gyroflow File: {:?}", e);
-           }
-       }
+   //---------------------------------------------------------
+   // Apply any new Keyframes from the host:
+   //---------------------------------------------------------
+   if keyframes::apply_keyframe_curves_if_changed(&unique_identifier_string, &manager, &cached_manager.applied_keyframes_revision) {
+       smoothing_changed = true;
+   }
 
-       cache.put(cache_key.to_owned(), Arc::new(manager));
-       cache.get(&cache_key).unwrap().clone()
-   };
+   //---------------------------------------------------------
+   // Keyframed parameters are evaluated by `gyroflow_core`,
+   // so we don't overwrite them with the per-frame values:
+   //---------------------------------------------------------
+   let keyframed = keyframes::KeyframedParameters::from_manager(&manager);
 
    {
        let mut params = manager.params.write();
@@ -1040,7 +1989,7 @@ pub extern "C" fn processFrame(
        //---------------------------------------------------------
        // Set the FOV:
        //---------------------------------------------------------
-       if params.fov != fov {
+       if !keyframed.fov && params.fov != fov {
            params.fov = fov;
            params_changed = true;
        }
@@ -1048,7 +1997,7 @@ pub extern "C" fn processFrame(
        //---------------------------------------------------------
        // Set the Lens Correction:
        //---------------------------------------------------------
-       if params.lens_correction_amount != lens_correction {
+       if !keyframed.lens_correction && params.lens_correction_amount != lens_correction {
            params.lens_correction_amount = lens_correction;
            params_changed = true;
        }
@@ -1056,7 +2005,7 @@ pub extern "C" fn processFrame(
        //---------------------------------------------------------
        // Set the Position Offset X:
        //---------------------------------------------------------
-       if params.adaptive_zoom_center_offset.0 != position_offset_x / 100.0 {
+       if !keyframed.position_offset_x && params.adaptive_zoom_center_offset.0 != position_offset_x / 100.0 {
             params.adaptive_zoom_center_offset.0 = position_offset_x / 100.0;
             params_changed = true;
        }
@@ -1064,7 +2013,7 @@ pub extern "C" fn processFrame(
        //---------------------------------------------------------
        // Set the Position Offset Y:
        //---------------------------------------------------------
-       if params.adaptive_zoom_center_offset.1 != position_offset_y / 100.0 {
+       if !keyframed.position_offset_y && params.adaptive_zoom_center_offset.1 != position_offset_y / 100.0 {
             params.adaptive_zoom_center_offset.1 = position_offset_y / 100.0;
             params_changed = true;
         }
@@ -1077,6 +2026,107 @@ pub extern "C" fn processFrame(
             params_changed = true;
             rotation_changed = true;
        }
+
+       //---------------------------------------------------------
+       // Set the Rolling Shutter Correction:
+       //---------------------------------------------------------
+       let incoming_frame_readout_time = if rolling_shutter_correction == 0 {
+           0.0
+       } else if frame_readout_time.is_nan() {
+           project_values.frame_readout_time
+       } else {
+           frame_readout_time
+       };
+       if params.frame_readout_time != incoming_frame_readout_time {
+            params.frame_readout_time = incoming_frame_readout_time;
+            params_changed = true;
+       }
+
+       let incoming_frame_readout_direction = readout_direction_from_index(frame_readout_direction).unwrap_or(project_values.frame_readout_direction);
+       if params.frame_readout_direction != incoming_frame_readout_direction {
+            params.frame_readout_direction = incoming_frame_readout_direction;
+            params_changed = true;
+       }
+
+       //---------------------------------------------------------
+       // Set the Adaptive Zoom Mode & Window:
+       //---------------------------------------------------------
+       let incoming_adaptive_zoom_mode = match adaptive_zoom_mode {
+           ADAPTIVE_ZOOM_MODE_DISABLED | ADAPTIVE_ZOOM_MODE_STATIC | ADAPTIVE_ZOOM_MODE_DYNAMIC => adaptive_zoom_mode,
+           _ => adaptive_zoom_mode_from_window(project_values.adaptive_zoom_window),
+       };
+       let incoming_adaptive_zoom_window = match incoming_adaptive_zoom_mode {
+           ADAPTIVE_ZOOM_MODE_DISABLED => -1.0,
+           ADAPTIVE_ZOOM_MODE_STATIC => 0.0,
+           _ => {
+               if adaptive_zoom_window > 0.0 {
+                   adaptive_zoom_window
+               } else if project_values.adaptive_zoom_window > 0.0 {
+                   project_values.adaptive_zoom_window
+               } else {
+                   DEFAULT_ADAPTIVE_ZOOM_WINDOW
+               }
+           }
+       };
+       if params.adaptive_zoom_window != incoming_adaptive_zoom_window {
+            params.adaptive_zoom_window = incoming_adaptive_zoom_window;
+            params_changed = true;
+       }
+
+       //---------------------------------------------------------
+       // Set the Maximum Zoom:
+       //---------------------------------------------------------
+       let incoming_max_zoom = if max_zoom.is_nan() {
+           project_values.max_zoom
+       } else if max_zoom > 0.0 {
+           Some(max_zoom)
+       } else {
+           None
+       };
+       if params.max_zoom != incoming_max_zoom {
+            params.max_zoom = incoming_max_zoom;
+            params_changed = true;
+       }
+
+       //---------------------------------------------------------
+       // Set the Light Refraction Coefficient:
+       //---------------------------------------------------------
+       let incoming_light_refraction_coefficient = if light_refraction_coefficient.is_nan() {
+           project_values.light_refraction_coefficient
+       } else {
+           light_refraction_coefficient
+       };
+       if params.light_refraction_coefficient != incoming_light_refraction_coefficient {
+            params.light_refraction_coefficient = incoming_light_refraction_coefficient;
+            params_changed = true;
+       }
+
+       //---------------------------------------------------------
+       // Set the Additional Rotation (applied on top of the
+       // smoothed camera orientation):
+       //---------------------------------------------------------
+       let incoming_additional_rotation = (
+           if additional_rotation_pitch.is_nan() { project_values.additional_rotation.0 } else { additional_rotation_pitch },
+           if additional_rotation_yaw.is_nan() { project_values.additional_rotation.1 } else { additional_rotation_yaw },
+           if additional_rotation_roll.is_nan() { project_values.additional_rotation.2 } else { additional_rotation_roll },
+       );
+       if params.additional_rotation != incoming_additional_rotation {
+            params.additional_rotation = incoming_additional_rotation;
+            params_changed = true;
+       }
+
+       //---------------------------------------------------------
+       // Set the Additional Translation:
+       //---------------------------------------------------------
+       let incoming_additional_translation = (
+           if additional_translation_x.is_nan() { project_values.additional_translation.0 } else { additional_translation_x },
+           if additional_translation_y.is_nan() { project_values.additional_translation.1 } else { additional_translation_y },
+           if additional_translation_z.is_nan() { project_values.additional_translation.2 } else { additional_translation_z },
+       );
+       if params.additional_translation != incoming_additional_translation {
+            params.additional_translation = incoming_additional_translation;
+            params_changed = true;
+       }
     }
 
    {
@@ -1084,17 +2134,24 @@ pub extern "C" fn processFrame(
        // Set the Smoothness:
        //---------------------------------------------------------
        let mut smoothing = manager.smoothing.write();
-       if smoothing.current().get_parameter("smoothness") != smoothness {
+       if !keyframed.smoothness && smoothing.current().get_parameter("smoothness") != smoothness {
            smoothing.current_mut().set_parameter("smoothness", smoothness);
-           params_changed = true;
+           smoothing_changed = true;
        }
 
        //---------------------------------------------------------
-       // Set the Horizon Lock:
+       // Set the Horizon Lock. The amount & roll are keyframed
+       // separately, so we keep applying the per-frame value of
+       // whichever one isn't keyframed. When the amount is
+       // keyframed, the lock just needs to be enabled:
        //---------------------------------------------------------
-       if smoothing.horizon_lock.lock_enabled != (horizon_lock > 0.0) || smoothing.horizon_lock.horizonlockpercent != horizon_lock || smoothing.horizon_lock.horizonroll != horizon_roll {
-          smoothing.horizon_lock.set_horizon(horizon_lock, horizon_roll, false, 0.0);
-          params_changed = true;
+       let incoming_horizon_lock = if keyframed.horizon_lock_amount { smoothing.horizon_lock.horizonlockpercent } else { horizon_lock };
+       let incoming_horizon_roll = if keyframed.horizon_roll { smoothing.horizon_lock.horizonroll } else { horizon_roll };
+       let incoming_lock_enabled = keyframed.horizon_lock_amount || incoming_horizon_lock > 0.0;
+       if smoothing.horizon_lock.lock_enabled != incoming_lock_enabled || smoothing.horizon_lock.horizonlockpercent != incoming_horizon_lock || smoothing.horizon_lock.horizonroll != incoming_horizon_roll {
+          smoothing.horizon_lock.set_horizon(incoming_horizon_lock, incoming_horizon_roll, false, 0.0);
+          smoothing.horizon_lock.lock_enabled = incoming_lock_enabled;
+          smoothing_changed = true;
        }
    }
 
@@ -1118,20 +2175,91 @@ pub extern "C" fn processFrame(
 
    //---------------------------------------------------------
    // If something has changed, Invalidate & Recompute, to
-   // make sure everything is up-to-date:
+   // make sure everything is up-to-date.
+   //
+   // On first load there's nothing to render with yet, so we
+   // wait for it (reusing the shared smoothing if another
+   // render size has already computed it). Otherwise we
+   // recompute in the background, and keep rendering with
+   // the last good result until it's ready. If only the
+   // size-dependent parameters have changed, or another
+   // render size has recomputed the shared smoothing, we
+   // don't need to recompute the smoothing:
+   //---------------------------------------------------------
+   if first_load {
+       log::info!("[Gyroflow Toolbox Rust] First load, so triggering Invalidate & Recompute...");
+       cached_render.recompute_blocking();
+       instance_state.has_recomputed = true;
+   } else if smoothing_changed {
+       log::info!("[Gyroflow Toolbox Rust] Smoothing changed, so triggering a background Invalidate & Recompute...");
+       cached_render.request_recompute(true);
+   } else if params_changed || cached_render.is_smoothing_outdated() {
+       log::info!("[Gyroflow Toolbox Rust] Parameters, rotation or shared smoothing changed, so triggering a background Recompute...");
+       cached_render.request_recompute(false);
+   }
+   drop(instance_state);
+
+   //---------------------------------------------------------
+   // On first load, update the estimated memory used by this
+   // manager, and make sure the cache is within its budget:
    //---------------------------------------------------------
-   if params_changed  {
-       log::info!("[Gyroflow Toolbox Rust] Parameters or rotation changed, so triggering Invalidate & Recompute...");
-       manager.invalidate_smoothing();
-       manager.recompute_blocking();
-       manager.params.write().calculate_ramped_timestamps(&manager.keyframes.read(), false, false);
+   if first_load {
+       cached_manager.update_estimated_size();
+       cache::enforce_memory_budget(Some(&cache_key));
    }
 
+   //---------------------------------------------------------
+   // Render the frame with the last recomputed manager:
+   //---------------------------------------------------------
+   render_frame(
+       &cached_render.manager(),
+       &pixel_format_string,
+       output_width,
+       output_height,
+       number_of_bytes_value,
+       timestamp,
+       input_rotation,
+       in_mtl_tex,
+       out_mtl_tex,
+       command_queue,
+   )
+}
+
+/// Renders a frame from the input Metal texture to the output Metal texture, using the supplied `StabilizationManager`.
+///
+/// # Arguments
+///
+/// * `manager` - The `StabilizationManager` to render with.
+/// * `pixel_format` - The pixel format of the Metal textures.
+/// * `output_width` - The width of the Metal textures.
+/// * `output_height` - The height of the Metal textures.
+/// * `number_of_bytes` - The number of bytes per channel.
+/// * `timestamp` - The timestamp of the video frame.
+/// * `input_rotation` - The input rotation of the video frame.
+/// * `in_mtl_tex` - A pointer to the input Metal texture.
+/// * `out_mtl_tex` - A pointer to the output Metal texture.
+/// * `command_queue` - A pointer to the Metal command queue.
+///
+/// # Returns
+///
+/// This function returns 1 if successful, otherwise 0.
+fn render_frame(
+    manager: &StabilizationManager,
+    pixel_format: &str,
+    output_width: usize,
+    output_height: usize,
+    number_of_bytes: usize,
+    timestamp: i64,
+    input_rotation: f64,
+    in_mtl_tex: *mut std::ffi::c_void,
+    out_mtl_tex: *mut std::ffi::c_void,
+    command_queue: *mut std::ffi::c_void,
+) -> std::ffi::c_int {
    //---------------------------------------------------------
    // Calculate buffer size and stride:
    //---------------------------------------------------------
-   let input_stride: usize = output_width * 4 * number_of_bytes_value;
-   let output_stride: usize = output_width * 4 * number_of_bytes_value;
+   let input_stride: usize = output_width * 4 * number_of_bytes;
+   let output_stride: usize = output_width * 4 * number_of_bytes;
 
    //---------------------------------------------------------
    // Prepare the Metal Texture Image Buffers:
@@ -1159,7 +2287,7 @@ pub extern "C" fn processFrame(
    //---------------------------------------------------------
    // Get the Stabilization Result:
    //---------------------------------------------------------
-   let _stabilization_result = match pixel_format_string.as_ref() {
+   let _stabilization_result = match pixel_format {
        "BGRA8Unorm" => {
            manager.process_pixels::<BGRA8>(timestamp, None, &mut buffers)
         },
@@ -1170,7 +2298,7 @@ pub extern "C" fn processFrame(
            manager.process_pixels::<RGBAf>(timestamp, None, &mut buffers)
         },
         e => {
-            log::error!("[Gyroflow Toolbox Rust] Unsupported pixel format: {:?}", pixel_format_string);
+            log::error!("[Gyroflow Toolbox Rust] Unsupported pixel format: {:?}", pixel_format);
             log::error!("[Gyroflow Toolbox Rust] Error during stabilization: {:?}", e);
             return 0
        }
@@ -1184,7 +2312,339 @@ pub extern "C" fn processFrame(
    //---------------------------------------------------------
    // Success:
    //---------------------------------------------------------
-   return 1
+   1
+}
+
+
+/// This function is called from Objective-C land to process a video frame with lens correction only.
+///
+/// Unlike `processFrame`, no Gyroflow Project or gyro data is required. The lens profile is loaded from a lens
+/// profile identifier (or path), and no stabilisation, horizon lock or rolling shutter correction is applied.
+///
+/// # Arguments
+///
+/// * `unique_identifier` - A pointer to a C-style string containing a unique identifier for the frame.
+/// * `width` - The width of the video frame.
+/// * `height` - The height of the video frame.
+/// * `pixel_format` - A pointer to a C-style string containing the pixel format of the video frame.
+/// * `number_of_bytes` - The number of bytes in the video frame.
+/// * `lens_profile` - A pointer to a C-style string containing the lens profile identifier or path.
+/// * `fps` - The frame rate of the video.
+/// * `duration_ms` - The duration of the video in milliseconds.
+/// * `timestamp` - The timestamp of the video frame.
+/// * `fov` - The field of view of the video frame.
+/// * `lens_correction` - The lens correction of the video frame.
+/// * `light_refraction_coefficient` - The light refraction coefficient (i.e. 1.0 for air, or 1.33 for footage shot through a flat port underwater), or `NaN` to use the default (air).
+/// * `input_rotation` - The input rotation of the video frame.
+/// * `disable_gyroflow_stretch` - Whether or not to disable Gyroflow stretch.
+/// * `in_mtl_tex` - A pointer to the input Metal texture.
+/// * `out_mtl_tex` - A pointer to the output Metal texture.
+/// * `command_queue` - A pointer to the Metal command queue.
+///
+/// # Returns
+///
+/// This function returns 1 if successful, otherwise 0. If successful, the output Metal Texture is stored in `out_mtl_tex`.
+#[unsafe(no_mangle)]
+pub extern "C" fn processLensCorrectionFrame(
+    unique_identifier: *const c_char,
+    width: u32,
+    height: u32,
+    pixel_format: *const c_char,
+    number_of_bytes: std::ffi::c_int,
+    lens_profile: *const c_char,
+    fps: f64,
+    duration_ms: f64,
+    timestamp: i64,
+    fov: f64,
+    lens_correction: f64,
+    light_refraction_coefficient: f64,
+    input_rotation: f64,
+    disable_gyroflow_stretch: u8,
+    in_mtl_tex: *mut std::ffi::c_void,
+    out_mtl_tex: *mut std::ffi::c_void,
+    command_queue: *mut std::ffi::c_void,
+) -> std::ffi::c_int {
+    //---------------------------------------------------------
+    // Have parameters changed:
+    //---------------------------------------------------------
+    let mut params_changed = false;
+
+    //---------------------------------------------------------
+    // Get the Unique Identifier, Pixel Format & Lens Profile:
+    //---------------------------------------------------------
+    let unique_identifier_pointer = unsafe { CStr::from_ptr(unique_identifier) };
+    let unique_identifier_string = unique_identifier_pointer.to_string_lossy();
+
+    let pixel_format_pointer = unsafe { CStr::from_ptr(pixel_format) };
+    let pixel_format_string = pixel_format_pointer.to_string_lossy();
+
+    let lens_profile_pointer = unsafe { CStr::from_ptr(lens_profile) };
+    let lens_profile_string = lens_profile_pointer.to_string_lossy();
+
+    let output_width: usize = width as usize;
+    let output_height: usize = height as usize;
+    let number_of_bytes_value: usize = number_of_bytes as usize;
+
+    //---------------------------------------------------------
+    // Cache the manager. The cache is only locked while we
+    // find the entry:
+    //---------------------------------------------------------
+    let cache_key = cache::CacheKey::lens_correction(&unique_identifier_string, &lens_profile_string, output_width, output_height, &pixel_format_string, disable_gyroflow_stretch != 0);
+    let cached_manager = cache::cache_entry(&cache_key).get_or_try_init(|| {
+        //---------------------------------------------------------
+        // Setup the Gyroflow Manager, using the shared Lens
+        // Profile Library:
+        //---------------------------------------------------------
+        let mut manager = StabilizationManager::default();
+        lens_library::use_lens_library(&mut manager);
+
+        //---------------------------------------------------------
+        // Initialise the manager without any gyro data:
+        //---------------------------------------------------------
+        let frame_count = (duration_ms * fps / 1000.0).ceil().max(1.0) as usize;
+        if let Err(e) = manager.init_from_video_data(duration_ms, fps, frame_count, (output_width, output_height)) {
+            log::error!("[Gyroflow Toolbox Rust] Failed to initialise lens correction: {:?}", e);
+            return Err(())
+        }
+
+        //---------------------------------------------------------
+        // Load the Lens Profile:
+        //---------------------------------------------------------
+        if let Err(e) = manager.load_lens_profile(&lens_profile_string) {
+            log::error!("[Gyroflow Toolbox Rust] Error loading Lens Profile: {:?}", e);
+            return Err(())
+        }
+
+        //---------------------------------------------------------
+        // Disable Gyroflow Stretch:
+        //---------------------------------------------------------
+        if disable_gyroflow_stretch != 0 {
+            manager.disable_lens_stretch(false);
+        }
+
+        {
+            let mut params = manager.params.write();
+
+            //---------------------------------------------------------
+            // Invert the Frame Buffer:
+            //---------------------------------------------------------
+            params.framebuffer_inverted = true;
+
+            //---------------------------------------------------------
+            // There's no camera motion, so use a static crop and
+            // disable Rolling Shutter Correction:
+            //---------------------------------------------------------
+            params.adaptive_zoom_window = 0.0;
+            params.frame_readout_time = 0.0;
+        }
+
+        //---------------------------------------------------------
+        // Make sure the Horizon Lock is disabled:
+        //---------------------------------------------------------
+        manager.smoothing.write().horizon_lock.set_horizon(0.0, 0.0, false, 0.0);
+
+        Ok(Arc::new(CachedManager::new(manager)))
+    });
+    let cached_manager = match cached_manager {
+        Ok(cached_manager) => cached_manager.clone(),
+        Err(_) => return 0,
+    };
+
+    //---------------------------------------------------------
+    // Get the render state (the render size is part of the
+    // cache key, so there's only ever one):
+    //---------------------------------------------------------
+    let cached_render = cached_manager.render(output_width, output_height, &pixel_format_string, |manager| {
+        //---------------------------------------------------------
+        // Set the Input & Output Size:
+        //---------------------------------------------------------
+        manager.set_size(output_width, output_height);
+        manager.set_output_size(output_width, output_height);
+
+        {
+            let mut stab = manager.stabilization.write();
+            stab.interpolation = gyroflow_core::stabilization::Interpolation::Lanczos4;
+            stab.share_wgpu_instances = true;
+        }
+
+        //---------------------------------------------------------
+        // Force the background color to transparent:
+        //---------------------------------------------------------
+        manager.set_background_color(Vector4::new(0.0, 0.0, 0.0, 0.0));
+    });
+
+    //---------------------------------------------------------
+    // The host's parameters are applied to the requested
+    // manager, which is only rendered with once it has been
+    // recomputed:
+    //---------------------------------------------------------
+    let manager = cached_render.requested.clone();
+
+    //---------------------------------------------------------
+    // Lock this instance while we apply the parameters:
+    //---------------------------------------------------------
+    let mut instance_state = cached_render.instance_state.lock().unwrap();
+    let first_load = !instance_state.has_recomputed;
+
+    {
+        let mut params = manager.params.write();
+
+        //---------------------------------------------------------
+        // Set the FOV:
+        //---------------------------------------------------------
+        if params.fov != fov {
+            params.fov = fov;
+            params_changed = true;
+        }
+
+        //---------------------------------------------------------
+        // Set the Lens Correction:
+        //---------------------------------------------------------
+        if params.lens_correction_amount != lens_correction {
+            params.lens_correction_amount = lens_correction;
+            params_changed = true;
+        }
+
+        //---------------------------------------------------------
+        // Set the Light Refraction Coefficient (there's no
+        // Gyroflow Project, so fall back to the default):
+        //---------------------------------------------------------
+        let incoming_light_refraction_coefficient = if light_refraction_coefficient.is_finite() && light_refraction_coefficient > 0.0 {
+            light_refraction_coefficient
+        } else {
+            DEFAULT_LIGHT_REFRACTION_COEFFICIENT
+        };
+        if params.light_refraction_coefficient != incoming_light_refraction_coefficient {
+            params.light_refraction_coefficient = incoming_light_refraction_coefficient;
+            params_changed = true;
+        }
+    }
+
+    //---------------------------------------------------------
+    // On first load there's nothing to render with yet, so we
+    // wait for the recompute. Otherwise we recompute in the
+    // background, and keep rendering with the last good result
+    // until it's ready. There's no camera motion, so only the
+    // zooming & undistortion need to be recomputed:
+    //---------------------------------------------------------
+    if first_load {
+        log::info!("[Gyroflow Toolbox Rust] First load, so triggering Invalidate & Recompute...");
+        cached_render.recompute_blocking();
+        instance_state.has_recomputed = true;
+    } else if params_changed {
+        log::info!("[Gyroflow Toolbox Rust] Lens correction parameters changed, so triggering a background Recompute...");
+        cached_render.request_recompute(false);
+    }
+    drop(instance_state);
+
+    //---------------------------------------------------------
+    // On first load, update the estimated memory used by this
+    // manager, and make sure the cache is within its budget:
+    //---------------------------------------------------------
+    if first_load {
+        cached_manager.update_estimated_size();
+        cache::enforce_memory_budget(Some(&cache_key));
+    }
+
+    //---------------------------------------------------------
+    // Render the frame with the last recomputed manager:
+    //---------------------------------------------------------
+    render_frame(
+        &cached_render.manager(),
+        &pixel_format_string,
+        output_width,
+        output_height,
+        number_of_bytes_value,
+        timestamp,
+        input_rotation,
+        in_mtl_tex,
+        out_mtl_tex,
+        command_queue,
+    )
+}
+
+/// This function is called from Objective-C land to apply the lens distortion of a Gyroflow Project to a clean
+/// image (for example a graphics layer), so that it matches footage that hasn't been undistorted.
+///
+/// # Arguments
+///
+/// * `unique_identifier` - A pointer to a C-style string containing a unique identifier for the frame.
+/// * `path` - A pointer to a C-style string containing the path to the Gyroflow Project.
+/// * `data` - A pointer to a C-style string containing the Gyroflow Project data.
+/// * `timestamp` - The timestamp of the video frame.
+/// * `apply_camera_motion` - Whether or not to also apply the per-frame camera motion from the gyro data.
+/// * `light_refraction_coefficient` - The light refraction coefficient (i.e. 1.33 for footage shot through a flat port underwater), or `NaN` to use the value from the Gyroflow Project.
+/// * `input_rotation` - The input rotation of the video frame.
+/// * `in_mtl_tex` - A pointer to the input Metal texture.
+/// * `out_mtl_tex` - A pointer to the output Metal texture.
+/// * `command_queue` - A pointer to the Metal command queue.
+///
+/// # Returns
+///
+/// This function returns 1 if successful, otherwise 0. If successful, the output Metal Texture is stored in `out_mtl_tex`.
+#[unsafe(no_mangle)]
+pub extern "C" fn processRedistortFrame(
+    unique_identifier: *const c_char,
+    path: *const c_char,
+    data: *const c_char,
+    timestamp: i64,
+    apply_camera_motion: u8,
+    light_refraction_coefficient: f64,
+    input_rotation: f64,
+    in_mtl_tex: *mut std::ffi::c_void,
+    out_mtl_tex: *mut std::ffi::c_void,
+    command_queue: *mut std::ffi::c_void,
+) -> std::ffi::c_int {
+    //---------------------------------------------------------
+    // Get the Unique Identifier & Path:
+    //---------------------------------------------------------
+    let unique_identifier_pointer = unsafe { CStr::from_ptr(unique_identifier) };
+    let unique_identifier_string = unique_identifier_pointer.to_string_lossy();
+
+    let path_pointer = unsafe { CStr::from_ptr(path) };
+    let path_string = path_pointer.to_string_lossy();
+
+    //---------------------------------------------------------
+    // Cache the manager. The cache is only locked while we
+    // find the entry:
+    //---------------------------------------------------------
+    let project_data = unsafe { CStr::from_ptr(data) }.to_bytes();
+    let cache_key = cache::CacheKey::redistort(&unique_identifier_string, &path_string, project_data);
+    let cached_manager = cache::cache_entry(&cache_key).get_or_try_init(|| {
+        //---------------------------------------------------------
+        // Import the Gyroflow Data & compute the smoothing, so
+        // that we know the camera motion:
+        //---------------------------------------------------------
+        let manager = StabilizationManager::default();
+        let mut is_preset = false;
+        if let Err(e) = manager.import_gyroflow_data(project_data, true, None, |_|(), Arc::new(AtomicBool::new(false)), &mut is_preset, true) {
+            log::error!("[Gyroflow Toolbox Rust] Failed to import Gyroflow File: {:?}", e);
+            return Err(())
+        }
+        manager.recompute_blocking();
+
+        //---------------------------------------------------------
+        // Invert the Frame Buffer, as in `processFrame`:
+        //---------------------------------------------------------
+        manager.params.write().framebuffer_inverted = true;
+
+        Ok(Arc::new(CachedManager::new(manager)))
+    });
+    let cached_manager = match cached_manager {
+        Ok(cached_manager) => cached_manager.clone(),
+        Err(_) => return 0,
+    };
+
+    //---------------------------------------------------------
+    // Re-distort the frame:
+    //---------------------------------------------------------
+    match redistort::redistort_frame(&cached_manager, timestamp, apply_camera_motion != 0, light_refraction_coefficient, input_rotation, in_mtl_tex, out_mtl_tex, command_queue) {
+        Ok(_) => 1,
+        Err(e) => {
+            log::error!("[Gyroflow Toolbox Rust] Error during re-distortion: {:?}", e);
+            0
+        }
+    }
 }
 
 #[unsafe(no_mangle)]
@@ -0,0 +1,718 @@
+//! # Gyroflow Toolbox: Media Import
+//!
+//! This module creates Gyroflow Projects from media files, along with motion data that isn't embedded in the clip
+//! itself, such as a standalone gyro log from a separate logger (using the video metadata supplied by the host), or
+//! a sidecar file saved next to the clip.
+//!
+//! Media files can also be probed, to describe what they contain before they're imported.
+//!
+//! It can also merge the chapters of a split recording (i.e. GoPro & DJI split long recordings into 4GB files) into
+//! a single Gyroflow Project, so that the smoothing is continuous across the joins.
+
+//---------------------------------------------------------
+// Local name bindings:
+//---------------------------------------------------------
+use gyroflow_core::StabilizationManager;
+use gyroflow_core::gyro_source::{ FileLoadOptions, FileMetadata, GyroSource };
+
+use crate::lens_library;
+use crate::lens_profiles::ClipMetadata;
+
+use serde::Serialize;                       // Allows us to serialize the sidecar files
+use std::path::Path;                        // Allows us to use `Path`
+use std::sync::Arc;                         // Adds Atomic Reference Count support
+use std::sync::atomic::AtomicBool;          // The AtomicBool type is a type of atomic variable that can be used in concurrent (multi-threaded) contexts.
+
+/// The video metadata supplied by the host, for motion data that isn't embedded in the clip.
+#[derive(Clone, Copy)]
+pub(crate) struct HostVideoMetadata {
+    pub width: usize,
+    pub height: usize,
+    pub fps: f64,
+    pub duration_s: f64,
+    pub start_offset_ms: f64,
+}
+
+impl HostVideoMetadata {
+    /// Checks that the metadata describes a real video.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err(format!("The video size must be greater than zero, not {}x{}.", self.width, self.height));
+        }
+        if !self.fps.is_finite() || self.fps <= 0.0 {
+            return Err(format!("The frame rate must be greater than zero, not {}.", self.fps));
+        }
+        if !self.duration_s.is_finite() || self.duration_s <= 0.0 {
+            return Err(format!("The duration must be greater than zero, not {}.", self.duration_s));
+        }
+        if !self.start_offset_ms.is_finite() {
+            return Err(format!("The start offset must be a number, not {}.", self.start_offset_ms));
+        }
+        Ok(())
+    }
+
+    fn frame_count(&self) -> usize {
+        (self.duration_s * self.fps).round().max(1.0) as usize
+    }
+}
+
+/// Creates a new `StabilizationManager` using the shared Lens Profile Library, initialised from the host's video
+/// metadata without any motion data.
+pub(crate) fn manager_from_host_metadata(metadata: &HostVideoMetadata) -> Result<StabilizationManager, String> {
+    metadata.validate()?;
+
+    let mut stab = StabilizationManager::default();
+    lens_library::use_lens_library(&mut stab);
+
+    stab.init_from_video_data(metadata.duration_s * 1000.0, metadata.fps, metadata.frame_count(), (metadata.width, metadata.height))
+        .map_err(|e| format!("The video metadata couldn't be used: {:?}", e))?;
+    Ok(stab)
+}
+
+/// Loads the motion data from a gyro log (i.e. `.gcsv`, Betaflight Blackbox `.bbl`/`.bfl`, or a camera sidecar)
+/// into a manager, using `gyroflow_core`'s gyro loaders.
+///
+/// # Returns
+///
+/// The motion data source detected by `gyroflow_core` (i.e. "Betaflight Blackbox"), if any.
+pub(crate) fn load_gyro_log(stab: &StabilizationManager, gyro_log_path: &str, start_offset_ms: f64) -> Result<Option<String>, String> {
+    let file_metadata = stab.load_gyro_data(gyro_log_path, true, &FileLoadOptions::default(), |_| (), Arc::new(AtomicBool::new(false)))
+        .map_err(|e| format!("The gyro log couldn't be loaded: {:?}", e))?;
+
+    if !has_motion_data(stab) {
+        return Err("The gyro log doesn't contain any motion data.".to_string());
+    }
+
+    //---------------------------------------------------------
+    // Apply the start offset between the video & the log:
+    //---------------------------------------------------------
+    if start_offset_ms != 0.0 {
+        stab.set_offset(0, start_offset_ms);
+    }
+
+    Ok(file_metadata.detected_source)
+}
+
+/// Creates a complete Gyroflow Project from a standalone gyro log & the host's video metadata.
+///
+/// # Returns
+///
+/// The Gyroflow Project.
+pub(crate) fn import_gyro_log(gyro_log_path: &str, metadata: &HostVideoMetadata) -> Result<String, String> {
+    let stab = manager_from_host_metadata(metadata)?;
+
+    let detected_source = load_gyro_log(&stab, gyro_log_path, metadata.start_offset_ms)?;
+    log::info!("[Gyroflow Toolbox Rust] Gyro log loaded successfully: {}", detected_source.as_deref().unwrap_or("Unknown source"));
+
+    export_project(&stab)
+}
+
+/// Exports a manager as a Gyroflow Project, including its gyro data.
+pub(crate) fn export_project(stab: &StabilizationManager) -> Result<String, String> {
+    stab.export_gyroflow_data(gyroflow_core::GyroflowProjectType::WithGyroData, "{}", None)
+        .map_err(|e| format!("The Gyroflow Project couldn't be exported: {:?}", e))
+}
+
+/// The kinds of sidecar file that can supply the motion data for a clip, in order of preference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SidecarKind {
+    GyroflowProject,
+    Gcsv,
+    Blackbox,
+    SonyXml,
+    DjiSrt,
+}
+
+/// A sidecar file found next to a clip.
+#[derive(Clone, Serialize)]
+pub(crate) struct Sidecar {
+    pub path: String,
+    pub kind: SidecarKind,
+}
+
+/// A media file that's been imported, along with the file that supplied its motion data.
+pub(crate) struct ImportedMedia {
+    pub stab: StabilizationManager,
+    pub motion_data_file: Option<String>,
+    pub detected_source: Option<String>,
+}
+
+/// Finds the sidecar files next to a clip, using the same naming conventions as Gyroflow (i.e. `C0001.MP4` can have
+/// `C0001.gyroflow`, `C0001.gcsv`, `C0001.bbl`, `C0001M01.XML` or `C0001.SRT` next to it). File names are matched
+/// case-insensitively.
+///
+/// # Returns
+///
+/// The sidecar files, best first.
+pub(crate) fn find_sidecars(media_file_path: &Path) -> Vec<Sidecar> {
+    let (Some(directory), Some(stem)) = (media_file_path.parent(), media_file_path.file_stem()) else {
+        return Vec::new();
+    };
+    let stem = stem.to_string_lossy().to_lowercase();
+
+    let Ok(entries) = std::fs::read_dir(if directory.as_os_str().is_empty() { Path::new(".") } else { directory }) else {
+        return Vec::new();
+    };
+
+    let mut sidecars: Vec<Sidecar> = entries.filter_map(|entry| {
+        let path = entry.ok()?.path();
+        if !path.is_file() || path == media_file_path {
+            return None;
+        }
+        let file_stem = path.file_stem()?.to_string_lossy().to_lowercase();
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+
+        let kind = match extension.as_str() {
+            "gyroflow" if file_stem == stem                             => SidecarKind::GyroflowProject,
+            "gcsv" if file_stem == stem                                 => SidecarKind::Gcsv,
+            "bbl" | "bfl" if file_stem == stem                          => SidecarKind::Blackbox,
+            "xml" if is_sony_sidecar_stem(&file_stem, &stem)            => SidecarKind::SonyXml,
+            "srt" if file_stem == stem                                  => SidecarKind::DjiSrt,
+            _ => return None,
+        };
+        Some(Sidecar { path: path.to_string_lossy().to_string(), kind })
+    }).collect();
+
+    sidecars.sort_by(|a, b| a.kind.cmp(&b.kind).then(a.path.cmp(&b.path)));
+    sidecars
+}
+
+/// Sony sidecars add "M01" (or another two digit number) to the clip name, i.e. `C0001M01.XML`.
+fn is_sony_sidecar_stem(file_stem: &str, stem: &str) -> bool {
+    file_stem.strip_prefix(stem)
+        .and_then(|suffix| suffix.strip_prefix('m'))
+        .map(|number| number.len() == 2 && number.chars().all(|c| c.is_ascii_digit()))
+        .unwrap_or(false)
+}
+
+/// Imports a media file, preferring a Gyroflow Project saved next to it, then its embedded telemetry, then any
+/// telemetry sidecar files next to it.
+pub(crate) fn import_media_file(media_file_path: &str) -> Result<ImportedMedia, String> {
+    let sidecars = find_sidecars(Path::new(media_file_path));
+
+    //---------------------------------------------------------
+    // A Gyroflow Project saved next to the clip already has
+    // everything we need:
+    //---------------------------------------------------------
+    for sidecar in sidecars.iter().filter(|sidecar| sidecar.kind == SidecarKind::GyroflowProject) {
+        match import_gyroflow_project_file(&sidecar.path) {
+            Ok(stab) => {
+                return Ok(ImportedMedia { stab, motion_data_file: Some(sidecar.path.clone()), detected_source: Some("Gyroflow Project".to_string()) });
+            },
+            Err(e) => log::error!("[Gyroflow Toolbox Rust] Ignoring Gyroflow Project sidecar {:?}: {}", sidecar.path, e),
+        }
+    }
+
+    //---------------------------------------------------------
+    // Load the video file & its embedded telemetry:
+    //---------------------------------------------------------
+    let mut stab = StabilizationManager::default();
+    lens_library::use_lens_library(&mut stab);
+
+    let video_error = match stab.load_video_file(media_file_path, None, true) {
+        Ok(file_metadata) => {
+            if has_motion_data(&stab) {
+                return Ok(ImportedMedia { stab, motion_data_file: Some(media_file_path.to_string()), detected_source: file_metadata.detected_source });
+            }
+            None
+        },
+        Err(e) => Some(format!("{:?}", e)),
+    };
+
+    //---------------------------------------------------------
+    // Otherwise, try the telemetry sidecar files (which need
+    // the video metadata from the clip):
+    //---------------------------------------------------------
+    if stab.params.read().fps > 0.0 {
+        for sidecar in sidecars.iter().filter(|sidecar| sidecar.kind != SidecarKind::GyroflowProject) {
+            match load_gyro_log(&stab, &sidecar.path, 0.0) {
+                Ok(detected_source) => {
+                    return Ok(ImportedMedia { stab, motion_data_file: Some(sidecar.path.clone()), detected_source });
+                },
+                Err(e) => log::info!("[Gyroflow Toolbox Rust] Ignoring sidecar {:?}: {}", sidecar.path, e),
+            }
+        }
+    }
+
+    match video_error {
+        Some(e) => Err(format!("The media file couldn't be loaded: {}", e)),
+        None => Ok(ImportedMedia { stab, motion_data_file: None, detected_source: None }),
+    }
+}
+
+/// The outcome of importing a media file.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MediaImportStatus {
+    Success,
+    CannotOpen,
+    NoTelemetry,
+    NoLensProfile,
+    ExportFailed,
+}
+
+/// The result of importing a media file, as returned to the host.
+#[derive(Serialize)]
+pub(crate) struct MediaImportResult {
+    pub result: MediaImportStatus,
+    pub message: String,
+    pub camera: Option<ClipMetadata>,
+    pub detected_source: Option<String>,
+    pub motion_data_file: Option<String>,
+    pub gyroflow_project: Option<String>,
+}
+
+/// Imports a media file, and describes exactly what was (and wasn't) found.
+///
+/// A Gyroflow Project is only included when there's motion data. It's still included when there's no lens profile,
+/// so that the host can ask for one.
+pub(crate) fn import_media_file_result(media_file_path: &str) -> MediaImportResult {
+    let imported_media = match import_media_file(media_file_path) {
+        Ok(imported_media) => imported_media,
+        Err(e) => {
+            return MediaImportResult {
+                result: MediaImportStatus::CannotOpen,
+                message: e,
+                camera: None,
+                detected_source: None,
+                motion_data_file: None,
+                gyroflow_project: None,
+            };
+        }
+    };
+
+    let mut result = MediaImportResult {
+        result: MediaImportStatus::Success,
+        message: String::new(),
+        camera: Some(ClipMetadata::from_manager(&imported_media.stab)),
+        detected_source: imported_media.detected_source,
+        motion_data_file: imported_media.motion_data_file,
+        gyroflow_project: None,
+    };
+
+    if result.motion_data_file.is_none() {
+        result.result = MediaImportStatus::NoTelemetry;
+        result.message = "The media file was opened, but no motion data was found in it or next to it.".to_string();
+        return result;
+    }
+
+    match export_project(&imported_media.stab) {
+        Ok(gyroflow_project) => result.gyroflow_project = Some(gyroflow_project),
+        Err(e) => {
+            result.result = MediaImportStatus::ExportFailed;
+            result.message = e;
+            return result;
+        }
+    }
+
+    if imported_media.stab.lens.read().calib_dimension.w > 0 {
+        result.message = "The media file was imported successfully.".to_string();
+    } else {
+        result.result = MediaImportStatus::NoLensProfile;
+        result.message = "Motion data was found, but no matching lens profile.".to_string();
+    }
+    result
+}
+
+/// Imports a Gyroflow Project file into a new manager.
+fn import_gyroflow_project_file(path: &str) -> Result<StabilizationManager, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+
+    let mut stab = StabilizationManager::default();
+    lens_library::use_lens_library(&mut stab);
+
+    let mut is_preset = false;
+    stab.import_gyroflow_data(&data, true, None, |_|(), Arc::new(AtomicBool::new(false)), &mut is_preset, true)
+        .map_err(|e| format!("{:?}", e))?;
+
+    if is_preset || !has_motion_data(&stab) {
+        return Err("The Gyroflow Project doesn't contain any motion data.".to_string());
+    }
+    Ok(stab)
+}
+
+/// Whether or not a manager has any motion data.
+pub(crate) fn has_motion_data(stab: &StabilizationManager) -> bool {
+    !stab.gyro.read().quaternions.is_empty()
+}
+
+/// A chapter of a split recording, and where it starts in the merged Gyroflow Project.
+#[derive(Serialize)]
+pub(crate) struct ChapterInfo {
+    pub path: String,
+    pub start_ms: f64,
+    pub duration_ms: f64,
+}
+
+/// The result of merging the chapters of a split recording.
+#[derive(Serialize)]
+pub(crate) struct MergedChapters {
+    pub gyroflow_project: String,
+    pub duration_ms: f64,
+    pub chapters: Vec<ChapterInfo>,
+}
+
+/// Merges the chapters of a split recording into a single Gyroflow Project, by concatenating their telemetry with
+/// each chapter offset by the duration of the chapters before it.
+///
+/// # Arguments
+///
+/// * `chapter_paths` - The chapter files, in recording order.
+///
+/// # Returns
+///
+/// The merged Gyroflow Project, along with where each chapter starts in it.
+pub(crate) fn merge_chapters(chapter_paths: &[String]) -> Result<MergedChapters, String> {
+    if chapter_paths.is_empty() {
+        return Err("There are no chapters to merge.".to_string());
+    }
+
+    //---------------------------------------------------------
+    // Load the telemetry of each chapter:
+    //---------------------------------------------------------
+    let mut chapters = Vec::with_capacity(chapter_paths.len());
+    let mut first_chapter_stab: Option<StabilizationManager> = None;
+    let mut merged_metadata: Option<FileMetadata> = None;
+    let mut video_size = (0, 0);
+    let mut fps = 0.0;
+    let mut frame_count = 0;
+    let mut start_ms = 0.0;
+
+    for (index, chapter_path) in chapter_paths.iter().enumerate() {
+        let mut chapter_stab = StabilizationManager::default();
+        lens_library::use_lens_library(&mut chapter_stab);
+
+        chapter_stab.load_video_file(chapter_path, None, true)
+            .map_err(|e| format!("Chapter {} ({:?}) couldn't be loaded: {:?}", index + 1, chapter_path, e))?;
+        if !has_motion_data(&chapter_stab) {
+            return Err(format!("Chapter {} ({:?}) doesn't contain any motion data.", index + 1, chapter_path));
+        }
+
+        //---------------------------------------------------------
+        // Take the telemetry that the gyro source actually loaded:
+        //---------------------------------------------------------
+        let mut chapter_metadata = chapter_stab.gyro.read().file_metadata.read().clone();
+
+        let (chapter_size, chapter_fps, chapter_duration_ms, chapter_frame_count) = {
+            let params = chapter_stab.params.read();
+            (params.video_size, params.fps, params.duration_ms, params.frame_count)
+        };
+
+        //---------------------------------------------------------
+        // Every chapter must come from the same recording:
+        //---------------------------------------------------------
+        if index == 0 {
+            video_size = chapter_size;
+            fps = chapter_fps;
+        } else if chapter_size != video_size || (chapter_fps - fps).abs() > 0.001 {
+            return Err(format!(
+                "Chapter {} ({:?}) is {}x{} at {} fps, but the first chapter is {}x{} at {} fps.",
+                index + 1, chapter_path, chapter_size.0, chapter_size.1, chapter_fps, video_size.0, video_size.1, fps
+            ));
+        }
+
+        trim_chapter_metadata(&mut chapter_metadata, chapter_duration_ms, chapter_frame_count);
+
+        match merged_metadata.as_mut() {
+            Some(merged) => append_chapter_metadata(merged, chapter_metadata, start_ms, frame_count),
+            None => merged_metadata = Some(chapter_metadata),
+        }
+
+        chapters.push(ChapterInfo { path: chapter_path.clone(), start_ms, duration_ms: chapter_duration_ms });
+        start_ms += chapter_duration_ms;
+        frame_count += chapter_frame_count;
+
+        if index == 0 {
+            first_chapter_stab = Some(chapter_stab);
+        }
+    }
+    let duration_ms = start_ms;
+
+    //---------------------------------------------------------
+    // Extend the first chapter's manager (which already has
+    // the lens profile, camera & parameters) to span every
+    // chapter, rather than loading the first chapter again:
+    //---------------------------------------------------------
+    let stab = first_chapter_stab.ok_or("There are no chapters to merge.")?;
+
+    stab.init_from_video_data(duration_ms, fps, frame_count, video_size)
+        .map_err(|e| format!("The merged video couldn't be initialised: {:?}", e))?;
+
+    if let Some(merged) = merged_metadata {
+        let mut gyro = stab.gyro.write();
+        gyro.load_from_telemetry(merged);
+        gyro.duration_ms = duration_ms;
+    }
+
+    log::info!("[Gyroflow Toolbox Rust] Merged {} chapters into a {:.3} second Gyroflow Project.", chapters.len(), duration_ms / 1000.0);
+
+    Ok(MergedChapters {
+        gyroflow_project: export_project(&stab)?,
+        duration_ms,
+        chapters,
+    })
+}
+
+/// Drops any telemetry outside of a chapter (i.e. samples logged after the last frame), so that it can't overlap
+/// the start of the next chapter once offset.
+fn trim_chapter_metadata(chapter: &mut FileMetadata, duration_ms: f64, frame_count: usize) {
+    let duration_us = (duration_ms * 1000.0).round() as i64;
+    let in_chapter = |timestamp_us: &i64| (0..duration_us).contains(timestamp_us);
+
+    chapter.raw_imu.retain(|sample| sample.timestamp_ms >= 0.0 && sample.timestamp_ms < duration_ms);
+    chapter.quaternions.retain(|timestamp_us, _| in_chapter(timestamp_us));
+    if let Some(orientations) = chapter.image_orientations.as_mut() {
+        orientations.retain(|timestamp_us, _| in_chapter(timestamp_us));
+    }
+    if let Some(gravity) = chapter.gravity_vectors.as_mut() {
+        gravity.retain(|timestamp_us, _| in_chapter(timestamp_us));
+    }
+    if let Some(offsets) = chapter.per_frame_time_offsets.as_mut() {
+        offsets.truncate(frame_count);
+    }
+}
+
+/// Appends the (trimmed) telemetry of a chapter to the merged telemetry, offset by the start of the chapter.
+///
+/// # Arguments
+///
+/// * `merged` - The telemetry of the previous chapters.
+/// * `chapter` - The telemetry of the chapter to append.
+/// * `start_ms` - The start of the chapter within the merged video, in milliseconds.
+/// * `start_frame` - The first frame of the chapter within the merged video.
+fn append_chapter_metadata(merged: &mut FileMetadata, chapter: FileMetadata, start_ms: f64, start_frame: usize) {
+    let start_us = (start_ms * 1000.0).round() as i64;
+
+    merged.raw_imu.extend(chapter.raw_imu.into_iter().map(|mut sample| {
+        sample.timestamp_ms += start_ms;
+        sample
+    }));
+    merged.quaternions.extend(chapter.quaternions.into_iter().map(|(timestamp_us, quaternion)| (timestamp_us + start_us, quaternion)));
+
+    //---------------------------------------------------------
+    // Earlier chapters might not have had this telemetry:
+    //---------------------------------------------------------
+    if let Some(chapter_orientations) = chapter.image_orientations {
+        merged.image_orientations.get_or_insert_with(Default::default)
+            .extend(chapter_orientations.into_iter().map(|(timestamp_us, quaternion)| (timestamp_us + start_us, quaternion)));
+    }
+    if let Some(chapter_gravity) = chapter.gravity_vectors {
+        merged.gravity_vectors.get_or_insert_with(Default::default)
+            .extend(chapter_gravity.into_iter().map(|(timestamp_us, vector)| (timestamp_us + start_us, vector)));
+    }
+    if let Some(chapter_offsets) = chapter.per_frame_time_offsets {
+        //---------------------------------------------------------
+        // The offsets are per frame, so pad any earlier frames
+        // without an offset to keep them lined up:
+        //---------------------------------------------------------
+        let merged_offsets = merged.per_frame_time_offsets.get_or_insert_with(Default::default);
+        merged_offsets.resize(start_frame, 0.0);
+        merged_offsets.extend(chapter_offsets);
+    }
+}
+
+/// What a media file contains, as returned to the host before importing it.
+#[derive(Serialize)]
+pub(crate) struct MediaProbe {
+    #[serde(flatten)]
+    pub camera: ClipMetadata,
+    pub duration_s: f64,
+    pub has_gyro: bool,
+    pub has_accelerometer: bool,
+    pub imu_sample_rate: Option<f64>,
+    pub detected_source: Option<String>,
+    pub detected_lens_profile: Option<String>,
+}
+
+/// Probes a media file without importing it. The video metadata & telemetry are parsed directly with
+/// `gyroflow_core`, without setting up a `StabilizationManager`, loading a lens profile or exporting a Gyroflow
+/// Project.
+pub(crate) fn probe_media_file(media_file_path: &str) -> Result<MediaProbe, String> {
+    let video_metadata = gyroflow_core::util::get_video_metadata(media_file_path)
+        .map_err(|e| format!("The media file couldn't be opened: {:?}", e))?;
+
+    //---------------------------------------------------------
+    // A media file without telemetry is still a valid probe:
+    //---------------------------------------------------------
+    let file_metadata = GyroSource::parse_telemetry_file(
+        media_file_path,
+        &FileLoadOptions::default(),
+        (video_metadata.width, video_metadata.height),
+        video_metadata.fps,
+        |_| (),
+        Arc::new(AtomicBool::new(false)),
+    ).unwrap_or_else(|e| {
+        log::info!("[Gyroflow Toolbox Rust] No telemetry found in {:?}: {:?}", media_file_path, e);
+        FileMetadata::default()
+    });
+
+    //---------------------------------------------------------
+    // Work out the IMU sample rate from the raw samples:
+    //---------------------------------------------------------
+    let raw_imu = &file_metadata.raw_imu;
+    let imu_sample_rate = match (raw_imu.first(), raw_imu.last()) {
+        (Some(first), Some(last)) if raw_imu.len() > 1 && last.timestamp_ms > first.timestamp_ms => {
+            Some((raw_imu.len() - 1) as f64 / ((last.timestamp_ms - first.timestamp_ms) / 1000.0))
+        },
+        _ => None,
+    };
+
+    let mut camera = ClipMetadata {
+        width: video_metadata.width,
+        height: video_metadata.height,
+        fps: video_metadata.fps,
+        ..Default::default()
+    };
+    if let Some(camera_id) = file_metadata.camera_identifier.as_ref() {
+        camera.brand = camera_id.brand.clone();
+        camera.model = camera_id.model.clone();
+        camera.lens = camera_id.lens_model.clone();
+    }
+
+    //---------------------------------------------------------
+    // The lens profile is either embedded in the telemetry, or
+    // found in the Lens Profile Library by camera identifier:
+    //---------------------------------------------------------
+    let detected_lens_profile = match file_metadata.lens_profile.as_ref() {
+        Some(lens_profile) => {
+            let identifier = lens_profile.as_str().or_else(|| lens_profile.get("identifier").and_then(|identifier| identifier.as_str()));
+            Some(identifier.filter(|identifier| !identifier.is_empty()).unwrap_or("Embedded lens profile").to_string())
+        },
+        None => file_metadata.camera_identifier.as_ref().and_then(|camera_id| {
+            let db = lens_library::lens_profile_db();
+            let db = db.read();
+            db.get_by_id(&camera_id.identifier).map(|profile| if profile.identifier.is_empty() { camera_id.identifier.clone() } else { profile.identifier.clone() })
+        }),
+    };
+
+    Ok(MediaProbe {
+        camera,
+        duration_s: video_metadata.duration_s,
+        has_gyro: raw_imu.iter().any(|sample| sample.gyro.is_some()) || !file_metadata.quaternions.is_empty(),
+        has_accelerometer: raw_imu.iter().any(|sample| sample.accl.is_some()),
+        imu_sample_rate,
+        detected_source: file_metadata.detected_source.clone(),
+        detected_lens_profile,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gyroflow_core::gyro_source::TimeIMU;
+    use nalgebra::{ UnitQuaternion, Vector3 };
+    use std::path::PathBuf;
+
+    /// Creates an empty temporary directory containing the supplied (empty) files.
+    fn directory_with_files(name: &str, files: &[&str]) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("gyroflow-toolbox-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        for file in files {
+            std::fs::write(directory.join(file), b"").unwrap();
+        }
+        directory
+    }
+
+    fn sidecar_kinds(directory: &Path, media_file: &str) -> Vec<SidecarKind> {
+        find_sidecars(&directory.join(media_file)).into_iter().map(|sidecar| sidecar.kind).collect()
+    }
+
+    #[test]
+    fn sidecars_are_sorted_best_first() {
+        let directory = directory_with_files("priority", &["C0001.MP4", "C0001.SRT", "C0001M01.XML", "C0001.bbl", "C0001.gcsv", "C0001.gyroflow"]);
+        assert_eq!(sidecar_kinds(&directory, "C0001.MP4"), vec![
+            SidecarKind::GyroflowProject,
+            SidecarKind::Gcsv,
+            SidecarKind::Blackbox,
+            SidecarKind::SonyXml,
+            SidecarKind::DjiSrt,
+        ]);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn sidecar_names_are_matched_case_insensitively() {
+        let directory = directory_with_files("case", &["C0001.MP4", "c0001.GCSV", "c0001m01.xml"]);
+        assert_eq!(sidecar_kinds(&directory, "C0001.MP4"), vec![SidecarKind::Gcsv, SidecarKind::SonyXml]);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn sony_sidecars_need_a_two_digit_number() {
+        let directory = directory_with_files("sony", &["C0001.MP4", "C0001M01.XML", "C0001M1.XML", "C0001M01A.XML"]);
+        let sidecars = find_sidecars(&directory.join("C0001.MP4"));
+        assert_eq!(sidecars.len(), 1);
+        assert_eq!(sidecars[0].kind, SidecarKind::SonyXml);
+        assert!(sidecars[0].path.ends_with("C0001M01.XML"));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn sidecars_of_other_clips_are_ignored() {
+        let directory = directory_with_files("other", &["C0001.MP4", "C0002.gcsv", "C00010.gcsv", "C0002M01.XML"]);
+        assert!(sidecar_kinds(&directory, "C0001.MP4").is_empty());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn sony_sidecar_stems() {
+        assert!(is_sony_sidecar_stem("c0001m01", "c0001"));
+        assert!(is_sony_sidecar_stem("c0001m99", "c0001"));
+        assert!(!is_sony_sidecar_stem("c0001m1", "c0001"));
+        assert!(!is_sony_sidecar_stem("c0001m01a", "c0001"));
+        assert!(!is_sony_sidecar_stem("c0001x01", "c0001"));
+        assert!(!is_sony_sidecar_stem("c0001", "c0001"));
+    }
+
+    /// Telemetry with an IMU sample, quaternion, image orientation & gravity vector at each of the timestamps.
+    fn chapter_metadata(timestamps_ms: &[f64]) -> FileMetadata {
+        let timestamps_us = timestamps_ms.iter().map(|timestamp_ms| (timestamp_ms * 1000.0).round() as i64);
+        FileMetadata {
+            raw_imu: timestamps_ms.iter().map(|&timestamp_ms| TimeIMU { timestamp_ms, gyro: Some([0.0; 3]), ..Default::default() }).collect(),
+            quaternions: timestamps_us.clone().map(|timestamp_us| (timestamp_us, UnitQuaternion::identity())).collect(),
+            image_orientations: Some(timestamps_us.clone().map(|timestamp_us| (timestamp_us, UnitQuaternion::identity())).collect()),
+            gravity_vectors: Some(timestamps_us.map(|timestamp_us| (timestamp_us, Vector3::zeros())).collect()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn chapter_telemetry_is_offset_by_the_chapter_start() {
+        let mut merged = chapter_metadata(&[0.0, 500.0]);
+        append_chapter_metadata(&mut merged, chapter_metadata(&[0.0, 500.0]), 1000.0, 30);
+
+        let raw_imu_timestamps: Vec<f64> = merged.raw_imu.iter().map(|sample| sample.timestamp_ms).collect();
+        assert_eq!(raw_imu_timestamps, vec![0.0, 500.0, 1000.0, 1500.0]);
+        assert_eq!(merged.quaternions.keys().copied().collect::<Vec<_>>(), vec![0, 500_000, 1_000_000, 1_500_000]);
+        assert_eq!(merged.image_orientations.unwrap().keys().copied().collect::<Vec<_>>(), vec![0, 500_000, 1_000_000, 1_500_000]);
+        assert_eq!(merged.gravity_vectors.unwrap().keys().copied().collect::<Vec<_>>(), vec![0, 500_000, 1_000_000, 1_500_000]);
+    }
+
+    #[test]
+    fn telemetry_outside_of_a_chapter_is_trimmed() {
+        let mut chapter = chapter_metadata(&[-10.0, 0.0, 999.0, 1000.0, 1010.0]);
+        chapter.per_frame_time_offsets = Some(vec![0.1; 32]);
+        trim_chapter_metadata(&mut chapter, 1000.0, 30);
+
+        let raw_imu_timestamps: Vec<f64> = chapter.raw_imu.iter().map(|sample| sample.timestamp_ms).collect();
+        assert_eq!(raw_imu_timestamps, vec![0.0, 999.0]);
+        assert_eq!(chapter.quaternions.keys().copied().collect::<Vec<_>>(), vec![0, 999_000]);
+        assert_eq!(chapter.image_orientations.unwrap().keys().copied().collect::<Vec<_>>(), vec![0, 999_000]);
+        assert_eq!(chapter.gravity_vectors.unwrap().keys().copied().collect::<Vec<_>>(), vec![0, 999_000]);
+        assert_eq!(chapter.per_frame_time_offsets.unwrap().len(), 30);
+    }
+
+    #[test]
+    fn telemetry_missing_from_earlier_chapters_is_still_merged() {
+        let mut merged = FileMetadata::default();
+        let mut chapter = chapter_metadata(&[0.0]);
+        chapter.per_frame_time_offsets = Some(vec![0.5, 0.5]);
+        append_chapter_metadata(&mut merged, chapter, 1000.0, 3);
+
+        assert_eq!(merged.image_orientations.unwrap().keys().copied().collect::<Vec<_>>(), vec![1_000_000]);
+        assert_eq!(merged.gravity_vectors.unwrap().keys().copied().collect::<Vec<_>>(), vec![1_000_000]);
+        assert_eq!(merged.per_frame_time_offsets.unwrap(), vec![0.0, 0.0, 0.0, 0.5, 0.5]);
+    }
+}
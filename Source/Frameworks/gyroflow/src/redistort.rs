@@ -0,0 +1,363 @@
+//! # Gyroflow Toolbox: Re-distortion
+//!
+//! This module applies the distortion of a Gyroflow Project's lens profile to a clean (rectilinear) image, so that
+//! graphics can be placed over footage that still has its original lens distortion.
+//!
+//! `gyroflow_core` only renders in the "undistort" direction, so we use our own Metal compute kernel. The lens model
+//! itself comes from `gyroflow_core`: every output pixel is undistorted with the same distortion model, digital lens,
+//! lens stretch & light refraction that `loadLensProfile` loads, into a map of camera rays. The map only depends on
+//! the lens & output size, so it's cached, and the kernel only applies the per-frame camera motion.
+
+//---------------------------------------------------------
+// Local name bindings:
+//---------------------------------------------------------
+use gyroflow_core::StabilizationManager;
+use gyroflow_core::stabilization::{ ComputeParams, undistort_points };
+
+use crate::cache::CachedManager;
+
+use lazy_static::*;                         // A macro for declaring lazily evaluated statics
+use nalgebra::Matrix3;                      // Allows us to use `Matrix3`
+use std::collections::HashMap;              // Allows us to use `HashMap`
+use std::sync::Arc;                         // Adds Atomic Reference Count support
+use std::sync::Mutex;                       // A mutual exclusion primitive useful for protecting shared data
+
+//---------------------------------------------------------
+// The spacing (in output pixels) of the points in the ray
+// map. The rays in between are interpolated:
+//---------------------------------------------------------
+const RAY_MAP_STEP: u32 = 8;
+
+//---------------------------------------------------------
+// The maximum number of ray maps to keep:
+//---------------------------------------------------------
+const MAXIMUM_RAY_MAPS: usize = 16;
+
+//---------------------------------------------------------
+// The Metal Shading Language source for the kernel:
+//---------------------------------------------------------
+const REDISTORT_KERNEL_SOURCE: &str = r#"
+#include <metal_stdlib>
+using namespace metal;
+
+struct RedistortParams {
+    float4 intrinsics;      // fx, fy, cx, cy
+    float4 rotation_row0;
+    float4 rotation_row1;
+    float4 rotation_row2;
+    float2 input_rotation;  // cos, sin
+    uint2 size;
+    uint2 map_size;
+    float map_step;
+    uint framebuffer_inverted;
+};
+
+kernel void redistort(texture2d<float, access::sample> input [[texture(0)]],
+                      texture2d<float, access::write> output [[texture(1)]],
+                      constant RedistortParams& params [[buffer(0)]],
+                      device const float2* rays [[buffer(1)]],
+                      uint2 gid [[thread_position_in_grid]]) {
+    if (gid.x >= params.size.x || gid.y >= params.size.y) {
+        return;
+    }
+
+    constexpr sampler bilinear(coord::pixel, address::clamp_to_zero, filter::linear);
+
+    float2 pixel = float2(gid) + 0.5;
+    if (params.framebuffer_inverted != 0) {
+        pixel.y = float(params.size.y) - pixel.y;
+    }
+
+    // Look up the camera ray of the output (lens distorted) pixel, which was undistorted by gyroflow_core:
+    float2 grid = clamp(pixel / params.map_step, float2(0.0), float2(params.map_size - 1));
+    uint2 g0 = uint2(floor(grid));
+    uint2 g1 = min(g0 + 1, params.map_size - 1);
+    float2 t = grid - float2(g0);
+    float2 r00 = rays[g0.y * params.map_size.x + g0.x];
+    float2 r10 = rays[g0.y * params.map_size.x + g1.x];
+    float2 r01 = rays[g1.y * params.map_size.x + g0.x];
+    float2 r11 = rays[g1.y * params.map_size.x + g1.x];
+    float2 undistorted = mix(mix(r00, r10, t.x), mix(r01, r11, t.x), t.y);
+    if (any(isnan(undistorted)) || any(isinf(undistorted))) {
+        output.write(float4(0.0), gid);
+        return;
+    }
+
+    // Apply the camera motion:
+    float3 ray = float3(undistorted, 1.0);
+    float3 rotated = float3(dot(params.rotation_row0.xyz, ray), dot(params.rotation_row1.xyz, ray), dot(params.rotation_row2.xyz, ray));
+    if (rotated.z <= 0.0) {
+        output.write(float4(0.0), gid);
+        return;
+    }
+
+    // Project back into the clean (rectilinear) input image, applying the input rotation around its centre:
+    float2 source = rotated.xy / rotated.z * params.intrinsics.xy + params.intrinsics.zw;
+    float2 centre = float2(params.size) * 0.5;
+    float2 offset = source - centre;
+    source = centre + float2(offset.x * params.input_rotation.x - offset.y * params.input_rotation.y,
+                             offset.x * params.input_rotation.y + offset.y * params.input_rotation.x);
+    if (params.framebuffer_inverted != 0) {
+        source.y = float(params.size.y) - source.y;
+    }
+
+    output.write(input.sample(bilinear, source), gid);
+}
+"#;
+
+/// The parameters passed to the Metal kernel. This must match `RedistortParams` in `REDISTORT_KERNEL_SOURCE`.
+#[repr(C)]
+struct RedistortParams {
+    intrinsics: [f32; 4],
+    rotation_row0: [f32; 4],
+    rotation_row1: [f32; 4],
+    rotation_row2: [f32; 4],
+    input_rotation: [f32; 2],
+    size: [u32; 2],
+    map_size: [u32; 2],
+    map_step: f32,
+    framebuffer_inverted: u32,
+}
+
+/// The compiled kernel and a fallback command queue for a single Metal device.
+struct RedistortPipeline {
+    pipeline_state: metal::ComputePipelineState,
+    command_queue: metal::CommandQueue,
+}
+
+/// What a ray map depends on. The lens profile of a `CachedManager` is never modified after import, so the
+/// manager's identifier stands in for the lens.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct RayMapKey {
+    device: u64,
+    manager_id: u64,
+    light_refraction_coefficient: u64,
+    width: u32,
+    height: u32,
+}
+
+/// The camera rays of the output pixels (on a `RAY_MAP_STEP` grid), along with the camera intrinsics used to
+/// project them back into the clean image.
+struct RayMap {
+    buffer: metal::Buffer,
+    map_size: [u32; 2],
+    intrinsics: [f32; 4],
+}
+
+// The compiled kernels, keyed by the Metal device registry ID.
+lazy_static! {
+    static ref PIPELINES: Mutex<HashMap<u64, RedistortPipeline>> = Mutex::new(HashMap::new());
+}
+
+// The ray maps, keyed by the cached manager (and so its lens) & output size.
+lazy_static! {
+    static ref RAY_MAPS: Mutex<HashMap<RayMapKey, Arc<RayMap>>> = Mutex::new(HashMap::new());
+}
+
+/// Applies the lens distortion (and optionally the per-frame camera motion) of the supplied `CachedManager` to the
+/// input Metal texture, writing the result to the output Metal texture.
+///
+/// # Arguments
+///
+/// * `cached_manager` - The `CachedManager` containing the lens profile and gyro data.
+/// * `timestamp` - The timestamp of the video frame in microseconds.
+/// * `apply_camera_motion` - Whether or not to move the image with the camera, instead of keeping it still.
+/// * `light_refraction_coefficient` - The light refraction coefficient, or `NaN` to use the value from the manager.
+/// * `input_rotation` - The rotation of the input image in degrees.
+/// * `in_mtl_tex` - A pointer to the input Metal texture.
+/// * `out_mtl_tex` - A pointer to the output Metal texture.
+/// * `command_queue` - A pointer to the Metal command queue, or null to use our own command queue.
+///
+/// # Returns
+///
+/// `Ok(())` if successful, otherwise an error message.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn redistort_frame(
+    cached_manager: &CachedManager,
+    timestamp: i64,
+    apply_camera_motion: bool,
+    light_refraction_coefficient: f64,
+    input_rotation: f64,
+    in_mtl_tex: *mut std::ffi::c_void,
+    out_mtl_tex: *mut std::ffi::c_void,
+    command_queue: *mut std::ffi::c_void,
+) -> Result<(), String> {
+    if in_mtl_tex.is_null() || out_mtl_tex.is_null() {
+        return Err("Missing Metal texture".to_string());
+    }
+
+    let input_texture: &metal::TextureRef = unsafe { &*(in_mtl_tex as *const metal::TextureRef) };
+    let output_texture: &metal::TextureRef = unsafe { &*(out_mtl_tex as *const metal::TextureRef) };
+
+    let width = output_texture.width() as u32;
+    let height = output_texture.height() as u32;
+    let device = output_texture.device();
+    let manager: &StabilizationManager = &cached_manager.manager;
+
+    //---------------------------------------------------------
+    // Get (or build) the ray map for the lens:
+    //---------------------------------------------------------
+    let ray_map = ray_map(cached_manager, device, width, height, light_refraction_coefficient)?;
+
+    //---------------------------------------------------------
+    // Get the camera motion, from the smoothed (stabilised)
+    // orientation back to the original orientation:
+    //---------------------------------------------------------
+    let rotation = if apply_camera_motion {
+        let timestamp_ms = timestamp as f64 / 1000.0;
+        let gyro = manager.gyro.read();
+        let original = gyro.org_quat_at_timestamp(timestamp_ms);
+        let smoothed = gyro.smoothed_quat_at_timestamp(timestamp_ms);
+        (smoothed.inverse() * original).to_rotation_matrix().into_inner()
+    } else {
+        Matrix3::identity()
+    };
+
+    let input_rotation_radians = input_rotation.to_radians();
+    let params = RedistortParams {
+        intrinsics: ray_map.intrinsics,
+        rotation_row0: [rotation[(0, 0)] as f32, rotation[(0, 1)] as f32, rotation[(0, 2)] as f32, 0.0],
+        rotation_row1: [rotation[(1, 0)] as f32, rotation[(1, 1)] as f32, rotation[(1, 2)] as f32, 0.0],
+        rotation_row2: [rotation[(2, 0)] as f32, rotation[(2, 1)] as f32, rotation[(2, 2)] as f32, 0.0],
+        input_rotation: [input_rotation_radians.cos() as f32, input_rotation_radians.sin() as f32],
+        size: [width, height],
+        map_size: ray_map.map_size,
+        map_step: RAY_MAP_STEP as f32,
+        framebuffer_inverted: manager.params.read().framebuffer_inverted as u32,
+    };
+
+    //---------------------------------------------------------
+    // Get (or compile) the kernel for this Metal device. The
+    // lock is only held while we find it:
+    //---------------------------------------------------------
+    let (pipeline_state, own_command_queue) = {
+        let mut pipelines = PIPELINES.lock().unwrap();
+        if !pipelines.contains_key(&device.registry_id()) {
+            let library = device.new_library_with_source(REDISTORT_KERNEL_SOURCE, &metal::CompileOptions::new())?;
+            let function = library.get_function("redistort", None)?;
+            let pipeline_state = device.new_compute_pipeline_state_with_function(&function)?;
+            pipelines.insert(device.registry_id(), RedistortPipeline {
+                pipeline_state,
+                command_queue: device.new_command_queue(),
+            });
+        }
+        let pipeline = pipelines.get(&device.registry_id()).unwrap();
+        (pipeline.pipeline_state.clone(), pipeline.command_queue.clone())
+    };
+
+    let command_queue: &metal::CommandQueueRef = if command_queue.is_null() {
+        &own_command_queue
+    } else {
+        unsafe { &*(command_queue as *const metal::CommandQueueRef) }
+    };
+
+    //---------------------------------------------------------
+    // Encode & run the kernel:
+    //---------------------------------------------------------
+    let command_buffer = command_queue.new_command_buffer();
+    let encoder = command_buffer.new_compute_command_encoder();
+    encoder.set_compute_pipeline_state(&pipeline_state);
+    encoder.set_texture(0, Some(input_texture));
+    encoder.set_texture(1, Some(output_texture));
+    encoder.set_bytes(0, std::mem::size_of::<RedistortParams>() as u64, &params as *const RedistortParams as *const std::ffi::c_void);
+    encoder.set_buffer(1, Some(&ray_map.buffer), 0);
+
+    let threadgroup_size = metal::MTLSize::new(16, 16, 1);
+    let threadgroup_count = metal::MTLSize::new((width as u64).div_ceil(16), (height as u64).div_ceil(16), 1);
+    encoder.dispatch_thread_groups(threadgroup_count, threadgroup_size);
+    encoder.end_encoding();
+
+    command_buffer.commit();
+    command_buffer.wait_until_completed();
+
+    Ok(())
+}
+
+/// Gets the cached ray map for a lens & output size, building it with `gyroflow_core` if needed.
+fn ray_map(cached_manager: &CachedManager, device: &metal::DeviceRef, width: u32, height: u32, light_refraction_coefficient: f64) -> Result<Arc<RayMap>, String> {
+    let manager: &StabilizationManager = &cached_manager.manager;
+    let light_refraction_coefficient = if light_refraction_coefficient.is_nan() {
+        manager.params.read().light_refraction_coefficient
+    } else {
+        light_refraction_coefficient
+    };
+
+    let key = RayMapKey {
+        device: device.registry_id(),
+        manager_id: cached_manager.id,
+        light_refraction_coefficient: light_refraction_coefficient.to_bits(),
+        width,
+        height,
+    };
+    if let Some(ray_map) = RAY_MAPS.lock().unwrap().get(&key) {
+        return Ok(ray_map.clone());
+    }
+
+    //---------------------------------------------------------
+    // Build the `gyroflow_core` compute parameters, which
+    // include the distortion model, digital lens, lens
+    // stretch & light refraction of the loaded lens profile:
+    //---------------------------------------------------------
+    let mut compute_params = ComputeParams::from_manager(manager);
+    compute_params.light_refraction_coefficient = light_refraction_coefficient;
+
+    {
+        let lens = manager.lens.read();
+        if lens.calib_dimension.w == 0 || lens.calib_dimension.h == 0 {
+            return Err("No lens profile loaded".to_string());
+        }
+    }
+    let (video_width, video_height) = manager.params.read().video_size;
+    if video_width == 0 || video_height == 0 {
+        return Err("The Gyroflow Project has no video size".to_string());
+    }
+
+    //---------------------------------------------------------
+    // Undistort a grid of output pixels (in video pixels):
+    //---------------------------------------------------------
+    let scale_x = video_width as f64 / width as f64;
+    let scale_y = video_height as f64 / height as f64;
+    let map_size = [width.div_ceil(RAY_MAP_STEP) + 1, height.div_ceil(RAY_MAP_STEP) + 1];
+
+    let mut points = Vec::with_capacity((map_size[0] * map_size[1]) as usize);
+    for y in 0..map_size[1] {
+        for x in 0..map_size[0] {
+            points.push((
+                ((x * RAY_MAP_STEP) as f64 * scale_x) as f32,
+                ((y * RAY_MAP_STEP) as f64 * scale_y) as f32,
+            ));
+        }
+    }
+
+    let camera_matrix = compute_params.camera_matrix;
+    let rays = undistort_points(&points, camera_matrix, &compute_params.distortion_coeffs, Matrix3::identity(), None, None, &compute_params, 1.0, 0.0, false);
+    if rays.len() != points.len() {
+        return Err("The lens model couldn't undistort the output".to_string());
+    }
+    let rays: Vec<[f32; 2]> = rays.into_iter().map(|(x, y)| [x, y]).collect();
+
+    //---------------------------------------------------------
+    // Scale the camera matrix from the video size to the
+    // output size:
+    //---------------------------------------------------------
+    let intrinsics = [
+        (camera_matrix[(0, 0)] / scale_x) as f32,
+        (camera_matrix[(1, 1)] / scale_y) as f32,
+        (camera_matrix[(0, 2)] / scale_x) as f32,
+        (camera_matrix[(1, 2)] / scale_y) as f32,
+    ];
+
+    let buffer = device.new_buffer_with_data(
+        rays.as_ptr() as *const std::ffi::c_void,
+        std::mem::size_of_val(rays.as_slice()) as u64,
+        metal::MTLResourceOptions::StorageModeShared,
+    );
+    let ray_map = Arc::new(RayMap { buffer, map_size, intrinsics });
+
+    let mut ray_maps = RAY_MAPS.lock().unwrap();
+    if ray_maps.len() >= MAXIMUM_RAY_MAPS {
+        ray_maps.clear();
+    }
+    ray_maps.insert(key, ray_map.clone());
+    Ok(ray_map)
+}
@@ -69,6 +69,23 @@ enum {
         kCB_VideoRotation                       = 180,
         kCB_VideoSpeed                          = 190,
     
+        kCB_RollingShutterCorrection            = 200,
+        kCB_FrameReadoutTime                    = 210,
+        kCB_FrameReadoutDirection               = 220,
+    
+        kCB_AdaptiveZoomMode                    = 230,
+        kCB_AdaptiveZoomWindow                  = 240,
+        kCB_MaxZoom                             = 250,
+    
+        kCB_LightRefractionCoefficient          = 260,
+    
+        kCB_AdditionalRotationPitch             = 270,
+        kCB_AdditionalRotationYaw               = 271,
+        kCB_AdditionalRotationRoll              = 272,
+        kCB_AdditionalTranslationX              = 280,
+        kCB_AdditionalTranslationY              = 281,
+        kCB_AdditionalTranslationZ              = 282,
+    
     //---------------------------------------------------------
     // Tools:
     //---------------------------------------------------------
@@ -28,6 +28,23 @@
 @property (nonatomic, strong, nullable) NSNumber *inputRotation;
 @property (nonatomic, strong, nullable) NSNumber *videoRotation;
 
+@property (nonatomic, strong, nullable) NSNumber *rollingShutterCorrection;
+@property (nonatomic, strong, nullable) NSNumber *frameReadoutTime;
+@property (nonatomic, strong, nullable) NSNumber *frameReadoutDirection;
+
+@property (nonatomic, strong, nullable) NSNumber *adaptiveZoomMode;
+@property (nonatomic, strong, nullable) NSNumber *adaptiveZoomWindow;
+@property (nonatomic, strong, nullable) NSNumber *maxZoom;
+
+@property (nonatomic, strong, nullable) NSNumber *lightRefractionCoefficient;
+
+@property (nonatomic, strong, nullable) NSNumber *additionalRotationPitch;
+@property (nonatomic, strong, nullable) NSNumber *additionalRotationYaw;
+@property (nonatomic, strong, nullable) NSNumber *additionalRotationRoll;
+@property (nonatomic, strong, nullable) NSNumber *additionalTranslationX;
+@property (nonatomic, strong, nullable) NSNumber *additionalTranslationY;
+@property (nonatomic, strong, nullable) NSNumber *additionalTranslationZ;
+
 @property (nonatomic, strong, nullable) NSNumber *fovOverview;
 @property (nonatomic, strong, nullable) NSNumber *disableGyroflowStretch;
 
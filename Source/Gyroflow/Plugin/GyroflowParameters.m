@@ -27,6 +27,19 @@ static NSString * const kPositionOffsetX        = @"positionOffsetX";
 static NSString * const kPositionOffsetY        = @"positionOffsetY";
 static NSString * const kInputRotation          = @"inputRotation";
 static NSString * const kVideoRotation          = @"videoRotation";
+static NSString * const kRollingShutterCorrection = @"rollingShutterCorrection";
+static NSString * const kFrameReadoutTime       = @"frameReadoutTime";
+static NSString * const kFrameReadoutDirection  = @"frameReadoutDirection";
+static NSString * const kAdaptiveZoomMode       = @"adaptiveZoomMode";
+static NSString * const kAdaptiveZoomWindow     = @"adaptiveZoomWindow";
+static NSString * const kMaxZoom                = @"maxZoom";
+static NSString * const kLightRefractionCoefficient = @"lightRefractionCoefficient";
+static NSString * const kAdditionalRotationPitch = @"additionalRotationPitch";
+static NSString * const kAdditionalRotationYaw = @"additionalRotationYaw";
+static NSString * const kAdditionalRotationRoll = @"additionalRotationRoll";
+static NSString * const kAdditionalTranslationX = @"additionalTranslationX";
+static NSString * const kAdditionalTranslationY = @"additionalTranslationY";
+static NSString * const kAdditionalTranslationZ = @"additionalTranslationZ";
 static NSString * const kFovOverview            = @"fovOverview";
 static NSString * const kDisableGyroflowStretch = @"disableGyroflowStretch";
 
@@ -53,6 +66,23 @@ static NSString * const kDisableGyroflowStretch = @"disableGyroflowStretch";
         _inputRotation          = [decoder decodeObjectOfClass:[NSNumber class] forKey:kInputRotation];
         _videoRotation          = [decoder decodeObjectOfClass:[NSNumber class] forKey:kVideoRotation];
 
+        _rollingShutterCorrection = [decoder decodeObjectOfClass:[NSNumber class] forKey:kRollingShutterCorrection];
+        _frameReadoutTime       = [decoder decodeObjectOfClass:[NSNumber class] forKey:kFrameReadoutTime];
+        _frameReadoutDirection  = [decoder decodeObjectOfClass:[NSNumber class] forKey:kFrameReadoutDirection];
+
+        _adaptiveZoomMode       = [decoder decodeObjectOfClass:[NSNumber class] forKey:kAdaptiveZoomMode];
+        _adaptiveZoomWindow     = [decoder decodeObjectOfClass:[NSNumber class] forKey:kAdaptiveZoomWindow];
+        _maxZoom                = [decoder decodeObjectOfClass:[NSNumber class] forKey:kMaxZoom];
+
+        _lightRefractionCoefficient = [decoder decodeObjectOfClass:[NSNumber class] forKey:kLightRefractionCoefficient];
+
+        _additionalRotationPitch = [decoder decodeObjectOfClass:[NSNumber class] forKey:kAdditionalRotationPitch];
+        _additionalRotationYaw  = [decoder decodeObjectOfClass:[NSNumber class] forKey:kAdditionalRotationYaw];
+        _additionalRotationRoll = [decoder decodeObjectOfClass:[NSNumber class] forKey:kAdditionalRotationRoll];
+        _additionalTranslationX = [decoder decodeObjectOfClass:[NSNumber class] forKey:kAdditionalTranslationX];
+        _additionalTranslationY = [decoder decodeObjectOfClass:[NSNumber class] forKey:kAdditionalTranslationY];
+        _additionalTranslationZ = [decoder decodeObjectOfClass:[NSNumber class] forKey:kAdditionalTranslationZ];
+
         _fovOverview            = [decoder decodeObjectOfClass:[NSNumber class] forKey:kFovOverview];
         _disableGyroflowStretch = [decoder decodeObjectOfClass:[NSNumber class] forKey:kDisableGyroflowStretch];
     }
@@ -76,6 +106,23 @@ static NSString * const kDisableGyroflowStretch = @"disableGyroflowStretch";
     [encoder encodeObject:self.inputRotation          forKey:kInputRotation];
     [encoder encodeObject:self.videoRotation          forKey:kVideoRotation];
 
+    [encoder encodeObject:self.rollingShutterCorrection forKey:kRollingShutterCorrection];
+    [encoder encodeObject:self.frameReadoutTime       forKey:kFrameReadoutTime];
+    [encoder encodeObject:self.frameReadoutDirection  forKey:kFrameReadoutDirection];
+
+    [encoder encodeObject:self.adaptiveZoomMode       forKey:kAdaptiveZoomMode];
+    [encoder encodeObject:self.adaptiveZoomWindow     forKey:kAdaptiveZoomWindow];
+    [encoder encodeObject:self.maxZoom                forKey:kMaxZoom];
+
+    [encoder encodeObject:self.lightRefractionCoefficient forKey:kLightRefractionCoefficient];
+
+    [encoder encodeObject:self.additionalRotationPitch forKey:kAdditionalRotationPitch];
+    [encoder encodeObject:self.additionalRotationYaw  forKey:kAdditionalRotationYaw];
+    [encoder encodeObject:self.additionalRotationRoll forKey:kAdditionalRotationRoll];
+    [encoder encodeObject:self.additionalTranslationX forKey:kAdditionalTranslationX];
+    [encoder encodeObject:self.additionalTranslationY forKey:kAdditionalTranslationY];
+    [encoder encodeObject:self.additionalTranslationZ forKey:kAdditionalTranslationZ];
+
     [encoder encodeObject:self.fovOverview            forKey:kFovOverview];
     [encoder encodeObject:self.disableGyroflowStretch forKey:kDisableGyroflowStretch];
 }
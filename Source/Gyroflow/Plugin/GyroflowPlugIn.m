@@ -74,6 +74,11 @@ typedef void (^BRAWCompletionHandler)(void);
         
         startLogger([gyroflowCoreLogPath UTF8String]);
         
+        //---------------------------------------------------------
+        // Load the Gyroflow Core Lens Profile Library:
+        //---------------------------------------------------------
+        loadLensLibrary();
+        
         //---------------------------------------------------------
         // Get the Lens Profiles path:
         //---------------------------------------------------------
@@ -817,6 +822,305 @@ typedef void (^BRAWCompletionHandler)(void);
             return NO;
         }
         
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Rolling Shutter Correction' Check Box
+        //---------------------------------------------------------
+        if (![paramAPI addToggleButtonWithName:@"Rolling Shutter Correction"
+                                   parameterID:kCB_RollingShutterCorrection
+                                  defaultValue:YES
+                                parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE]) {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_RollingShutterCorrection"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Frame Readout Time' Slider
+        //
+        // NOTE: In milliseconds. Zero uses the frame readout
+        //       time from the Gyroflow Project.
+        //---------------------------------------------------------
+        if (![paramAPI addFloatSliderWithName:@"Frame Readout Time"
+                                  parameterID:kCB_FrameReadoutTime
+                                 defaultValue:0.0
+                                 parameterMin:0.0
+                                 parameterMax:1000.0
+                                    sliderMin:0.0
+                                    sliderMax:50.0
+                                        delta:0.01
+                               parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE])
+        {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_FrameReadoutTime"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Frame Readout Direction' Popup
+        //
+        // NOTE: The entries after 'Gyroflow Project' match the
+        //       order of `readout_direction_from_index` in Rust.
+        //---------------------------------------------------------
+        if (![paramAPI addPopupMenuWithName:@"Frame Readout Direction"
+                                parameterID:kCB_FrameReadoutDirection
+                               defaultValue:0
+                                menuEntries:@[@"Gyroflow Project", @"Top to Bottom", @"Bottom to Top", @"Left to Right", @"Right to Left"]
+                             parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE])
+        {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_FrameReadoutDirection"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Adaptive Zoom' Popup
+        //
+        // NOTE: The entries after 'Gyroflow Project' match the
+        //       `ADAPTIVE_ZOOM_MODE_*` constants in Rust.
+        //---------------------------------------------------------
+        if (![paramAPI addPopupMenuWithName:@"Adaptive Zoom"
+                                parameterID:kCB_AdaptiveZoomMode
+                               defaultValue:0
+                                menuEntries:@[@"Gyroflow Project", @"Disabled", @"Static Crop", @"Dynamic Zoom"]
+                             parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE])
+        {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_AdaptiveZoomMode"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Adaptive Zoom Window' Slider
+        //
+        // NOTE: In seconds, and only used for 'Dynamic Zoom'.
+        //       Zero uses the window from the Gyroflow Project.
+        //---------------------------------------------------------
+        if (![paramAPI addFloatSliderWithName:@"Adaptive Zoom Window"
+                                  parameterID:kCB_AdaptiveZoomWindow
+                                 defaultValue:0.0
+                                 parameterMin:0.0
+                                 parameterMax:30.0
+                                    sliderMin:0.0
+                                    sliderMax:15.0
+                                        delta:0.1
+                               parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE])
+        {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_AdaptiveZoomWindow"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Max Zoom' Slider
+        //
+        // NOTE: In percent. Zero uses the maximum zoom from the
+        //       Gyroflow Project.
+        //---------------------------------------------------------
+        if (![paramAPI addFloatSliderWithName:@"Max Zoom"
+                                  parameterID:kCB_MaxZoom
+                                 defaultValue:0.0
+                                 parameterMin:0.0
+                                 parameterMax:1000.0
+                                    sliderMin:0.0
+                                    sliderMax:300.0
+                                        delta:0.1
+                               parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE])
+        {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_MaxZoom"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Light Refraction Coefficient' Slider
+        //
+        // NOTE: 1.0 for air, 1.33 for water. Zero uses the
+        //       coefficient from the Gyroflow Project.
+        //---------------------------------------------------------
+        if (![paramAPI addFloatSliderWithName:@"Light Refraction Coefficient"
+                                  parameterID:kCB_LightRefractionCoefficient
+                                 defaultValue:0.0
+                                 parameterMin:0.0
+                                 parameterMax:3.0
+                                    sliderMin:0.0
+                                    sliderMax:2.0
+                                        delta:0.01
+                               parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE])
+        {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_LightRefractionCoefficient"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Additional Pitch' Slider
+        //
+        // NOTE: In degrees, applied on top of the smoothed
+        //       camera orientation.
+        //---------------------------------------------------------
+        if (![paramAPI addFloatSliderWithName:@"Additional Pitch"
+                                  parameterID:kCB_AdditionalRotationPitch
+                                 defaultValue:0.0
+                                 parameterMin:-180.0
+                                 parameterMax:180.0
+                                    sliderMin:-180.0
+                                    sliderMax:180.0
+                                        delta:0.1
+                               parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE])
+        {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_AdditionalRotationPitch"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Additional Yaw' Slider
+        //
+        // NOTE: In degrees, applied on top of the smoothed
+        //       camera orientation.
+        //---------------------------------------------------------
+        if (![paramAPI addFloatSliderWithName:@"Additional Yaw"
+                                  parameterID:kCB_AdditionalRotationYaw
+                                 defaultValue:0.0
+                                 parameterMin:-180.0
+                                 parameterMax:180.0
+                                    sliderMin:-180.0
+                                    sliderMax:180.0
+                                        delta:0.1
+                               parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE])
+        {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_AdditionalRotationYaw"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Additional Roll' Slider
+        //
+        // NOTE: In degrees, applied on top of the smoothed
+        //       camera orientation.
+        //---------------------------------------------------------
+        if (![paramAPI addFloatSliderWithName:@"Additional Roll"
+                                  parameterID:kCB_AdditionalRotationRoll
+                                 defaultValue:0.0
+                                 parameterMin:-180.0
+                                 parameterMax:180.0
+                                    sliderMin:-180.0
+                                    sliderMax:180.0
+                                        delta:0.1
+                               parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE])
+        {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_AdditionalRotationRoll"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Additional Translation X' Slider
+        //---------------------------------------------------------
+        if (![paramAPI addFloatSliderWithName:@"Additional Translation X"
+                                  parameterID:kCB_AdditionalTranslationX
+                                 defaultValue:0.0
+                                 parameterMin:-1000.0
+                                 parameterMax:1000.0
+                                    sliderMin:-100.0
+                                    sliderMax:100.0
+                                        delta:0.1
+                               parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE])
+        {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_AdditionalTranslationX"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Additional Translation Y' Slider
+        //---------------------------------------------------------
+        if (![paramAPI addFloatSliderWithName:@"Additional Translation Y"
+                                  parameterID:kCB_AdditionalTranslationY
+                                 defaultValue:0.0
+                                 parameterMin:-1000.0
+                                 parameterMax:1000.0
+                                    sliderMin:-100.0
+                                    sliderMax:100.0
+                                        delta:0.1
+                               parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE])
+        {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_AdditionalTranslationY"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
+        //---------------------------------------------------------
+        // ADD PARAMETER: 'Additional Translation Z' Slider
+        //---------------------------------------------------------
+        if (![paramAPI addFloatSliderWithName:@"Additional Translation Z"
+                                  parameterID:kCB_AdditionalTranslationZ
+                                 defaultValue:0.0
+                                 parameterMin:-1000.0
+                                 parameterMax:1000.0
+                                    sliderMin:-100.0
+                                    sliderMax:100.0
+                                        delta:0.1
+                               parameterFlags:kFxParameterFlag_DEFAULT | kFxParameterFlag_NOT_ANIMATABLE])
+        {
+            if (error != NULL) {
+                NSDictionary* userInfo = @{NSLocalizedDescriptionKey : @"[Gyroflow Toolbox Renderer] Unable to add parameter: kCB_AdditionalTranslationZ"};
+                *error = [NSError errorWithDomain:FxPlugErrorDomain
+                                             code:kFxError_InvalidParameter
+                                         userInfo:userInfo];
+            }
+            return NO;
+        }
+        
         //---------------------------------------------------------
         // END GROUP: 'Gyroflow Parameters'
         //---------------------------------------------------------
@@ -1380,6 +1684,97 @@ typedef void (^BRAWCompletionHandler)(void);
     [paramGetAPI getFloatValue:&videoRotation fromParameter:kCB_VideoRotation atTime:renderTime];
     params.videoRotation = [NSNumber numberWithDouble:videoRotation];
 
+    //---------------------------------------------------------
+    // Rolling Shutter Correction:
+    //---------------------------------------------------------
+    BOOL rollingShutterCorrection;
+    [paramGetAPI getBoolValue:&rollingShutterCorrection fromParameter:kCB_RollingShutterCorrection atTime:renderTime];
+    params.rollingShutterCorrection = [NSNumber numberWithBool:rollingShutterCorrection];
+
+    //---------------------------------------------------------
+    // Frame Readout Time:
+    //---------------------------------------------------------
+    double frameReadoutTime;
+    [paramGetAPI getFloatValue:&frameReadoutTime fromParameter:kCB_FrameReadoutTime atTime:renderTime];
+    params.frameReadoutTime = [NSNumber numberWithDouble:frameReadoutTime];
+
+    //---------------------------------------------------------
+    // Frame Readout Direction:
+    //---------------------------------------------------------
+    int frameReadoutDirection;
+    [paramGetAPI getIntValue:&frameReadoutDirection fromParameter:kCB_FrameReadoutDirection atTime:renderTime];
+    params.frameReadoutDirection = [NSNumber numberWithInt:frameReadoutDirection];
+
+    //---------------------------------------------------------
+    // Adaptive Zoom Mode:
+    //---------------------------------------------------------
+    int adaptiveZoomMode;
+    [paramGetAPI getIntValue:&adaptiveZoomMode fromParameter:kCB_AdaptiveZoomMode atTime:renderTime];
+    params.adaptiveZoomMode = [NSNumber numberWithInt:adaptiveZoomMode];
+
+    //---------------------------------------------------------
+    // Adaptive Zoom Window:
+    //---------------------------------------------------------
+    double adaptiveZoomWindow;
+    [paramGetAPI getFloatValue:&adaptiveZoomWindow fromParameter:kCB_AdaptiveZoomWindow atTime:renderTime];
+    params.adaptiveZoomWindow = [NSNumber numberWithDouble:adaptiveZoomWindow];
+
+    //---------------------------------------------------------
+    // Max Zoom:
+    //---------------------------------------------------------
+    double maxZoom;
+    [paramGetAPI getFloatValue:&maxZoom fromParameter:kCB_MaxZoom atTime:renderTime];
+    params.maxZoom = [NSNumber numberWithDouble:maxZoom];
+
+    //---------------------------------------------------------
+    // Light Refraction Coefficient:
+    //---------------------------------------------------------
+    double lightRefractionCoefficient;
+    [paramGetAPI getFloatValue:&lightRefractionCoefficient fromParameter:kCB_LightRefractionCoefficient atTime:renderTime];
+    params.lightRefractionCoefficient = [NSNumber numberWithDouble:lightRefractionCoefficient];
+
+    //---------------------------------------------------------
+    // Additional Pitch:
+    //---------------------------------------------------------
+    double additionalRotationPitch;
+    [paramGetAPI getFloatValue:&additionalRotationPitch fromParameter:kCB_AdditionalRotationPitch atTime:renderTime];
+    params.additionalRotationPitch = [NSNumber numberWithDouble:additionalRotationPitch];
+
+    //---------------------------------------------------------
+    // Additional Yaw:
+    //---------------------------------------------------------
+    double additionalRotationYaw;
+    [paramGetAPI getFloatValue:&additionalRotationYaw fromParameter:kCB_AdditionalRotationYaw atTime:renderTime];
+    params.additionalRotationYaw = [NSNumber numberWithDouble:additionalRotationYaw];
+
+    //---------------------------------------------------------
+    // Additional Roll:
+    //---------------------------------------------------------
+    double additionalRotationRoll;
+    [paramGetAPI getFloatValue:&additionalRotationRoll fromParameter:kCB_AdditionalRotationRoll atTime:renderTime];
+    params.additionalRotationRoll = [NSNumber numberWithDouble:additionalRotationRoll];
+
+    //---------------------------------------------------------
+    // Additional Translation X:
+    //---------------------------------------------------------
+    double additionalTranslationX;
+    [paramGetAPI getFloatValue:&additionalTranslationX fromParameter:kCB_AdditionalTranslationX atTime:renderTime];
+    params.additionalTranslationX = [NSNumber numberWithDouble:additionalTranslationX];
+
+    //---------------------------------------------------------
+    // Additional Translation Y:
+    //---------------------------------------------------------
+    double additionalTranslationY;
+    [paramGetAPI getFloatValue:&additionalTranslationY fromParameter:kCB_AdditionalTranslationY atTime:renderTime];
+    params.additionalTranslationY = [NSNumber numberWithDouble:additionalTranslationY];
+
+    //---------------------------------------------------------
+    // Additional Translation Z:
+    //---------------------------------------------------------
+    double additionalTranslationZ;
+    [paramGetAPI getFloatValue:&additionalTranslationZ fromParameter:kCB_AdditionalTranslationZ atTime:renderTime];
+    params.additionalTranslationZ = [NSNumber numberWithDouble:additionalTranslationZ];
+
     //---------------------------------------------------------
     // FOV Overview:
     //---------------------------------------------------------
@@ -1723,6 +2118,20 @@ typedef void (^BRAWCompletionHandler)(void);
     NSNumber *inputRotation             = params.inputRotation;
     NSNumber *videoRotation             = params.videoRotation;
     
+    NSNumber *rollingShutterCorrection  = params.rollingShutterCorrection;
+    NSNumber *frameReadoutTime          = params.frameReadoutTime;
+    NSNumber *frameReadoutDirection     = params.frameReadoutDirection;
+    NSNumber *adaptiveZoomMode          = params.adaptiveZoomMode;
+    NSNumber *adaptiveZoomWindow        = params.adaptiveZoomWindow;
+    NSNumber *maxZoom                   = params.maxZoom;
+    NSNumber *lightRefractionCoefficient = params.lightRefractionCoefficient;
+    NSNumber *additionalRotationPitch   = params.additionalRotationPitch;
+    NSNumber *additionalRotationYaw     = params.additionalRotationYaw;
+    NSNumber *additionalRotationRoll    = params.additionalRotationRoll;
+    NSNumber *additionalTranslationX    = params.additionalTranslationX;
+    NSNumber *additionalTranslationY    = params.additionalTranslationY;
+    NSNumber *additionalTranslationZ    = params.additionalTranslationZ;
+    
     NSNumber *fovOverview               = params.fovOverview;
     NSNumber *disableGyroflowStretch    = params.disableGyroflowStretch;
     
@@ -1828,6 +2237,39 @@ typedef void (^BRAWCompletionHandler)(void);
     uint8_t         xFOVOverview               = [fovOverview unsignedCharValue];
     uint8_t         xDisableGyroflowStretch    = [disableGyroflowStretch unsignedCharValue];
     
+    //---------------------------------------------------------
+    // Rolling Shutter Correction (a zero Frame Readout Time or
+    // the 'Gyroflow Project' direction uses the values from
+    // the Gyroflow Project):
+    //---------------------------------------------------------
+    uint8_t         xRollingShutterCorrection  = [rollingShutterCorrection unsignedCharValue];
+    double          xFrameReadoutTime          = [frameReadoutTime doubleValue] > 0.0 ? [frameReadoutTime doubleValue] : NAN;
+    uint8_t         xFrameReadoutDirection     = [frameReadoutDirection intValue] > 0 ? (uint8_t)([frameReadoutDirection intValue] - 1) : UINT8_MAX;
+    
+    //---------------------------------------------------------
+    // Adaptive Zoom ('Gyroflow Project', or a zero window or
+    // maximum zoom uses the values from the Gyroflow Project):
+    //---------------------------------------------------------
+    uint8_t         xAdaptiveZoomMode          = [adaptiveZoomMode intValue] > 0 ? (uint8_t)([adaptiveZoomMode intValue] - 1) : UINT8_MAX;
+    double          xAdaptiveZoomWindow        = [adaptiveZoomWindow doubleValue] > 0.0 ? [adaptiveZoomWindow doubleValue] : NAN;
+    double          xMaxZoom                   = [maxZoom doubleValue] > 0.0 ? [maxZoom doubleValue] : NAN;
+    
+    //---------------------------------------------------------
+    // Light Refraction Coefficient (zero uses the value from
+    // the Gyroflow Project):
+    //---------------------------------------------------------
+    double          xLightRefractionCoefficient = [lightRefractionCoefficient doubleValue] > 0.0 ? [lightRefractionCoefficient doubleValue] : NAN;
+    
+    //---------------------------------------------------------
+    // Additional Rotation & Translation:
+    //---------------------------------------------------------
+    double          xAdditionalRotationPitch   = [additionalRotationPitch doubleValue];
+    double          xAdditionalRotationYaw     = [additionalRotationYaw doubleValue];
+    double          xAdditionalRotationRoll    = [additionalRotationRoll doubleValue];
+    double          xAdditionalTranslationX    = [additionalTranslationX doubleValue];
+    double          xAdditionalTranslationY    = [additionalTranslationY doubleValue];
+    double          xAdditionalTranslationZ    = [additionalTranslationZ doubleValue];
+    
     //---------------------------------------------------------
     // Trigger the Gyroflow Rust Function:
     //---------------------------------------------------------
@@ -1851,6 +2293,19 @@ typedef void (^BRAWCompletionHandler)(void);
                           xVideoRotation,                       // double
                           xFOVOverview,                         // uint8_t
                           xDisableGyroflowStretch,              // uint8_t
+                          xRollingShutterCorrection,            // uint8_t
+                          xFrameReadoutTime,                    // double
+                          xFrameReadoutDirection,               // uint8_t
+                          xAdaptiveZoomMode,                    // uint8_t
+                          xAdaptiveZoomWindow,                  // double
+                          xMaxZoom,                             // double
+                          xLightRefractionCoefficient,          // double
+                          xAdditionalRotationPitch,             // double
+                          xAdditionalRotationYaw,               // double
+                          xAdditionalRotationRoll,              // double
+                          xAdditionalTranslationX,              // double
+                          xAdditionalTranslationY,              // double
+                          xAdditionalTranslationZ,              // double
                           (__bridge void *)inputTexture,        // MTLTexture
                           (__bridge void *)outputTexture,       // MTLTexture
                           nil                                   // MTLCommandQueue
@@ -2579,9 +3034,13 @@ typedef void (^BRAWCompletionHandler)(void);
     [paramSetAPI setStringParameterValue:base64EncodedString toParameter:kCB_GyroflowProjectData];
     
     //---------------------------------------------------------
-    // Trash the cache!
+    // Trash the cache for this instance only:
     //---------------------------------------------------------
-    trashCache();
+    NSString *uniqueIdentifier = nil;
+    [paramGetAPI getStringParameterValue:&uniqueIdentifier fromParameter:kCB_UniqueIdentifier];
+    if (uniqueIdentifier != nil) {
+        trashCacheForUniqueIdentifier([uniqueIdentifier UTF8String]);
+    }
         
     //---------------------------------------------------------
     // Show success message:
@@ -4588,6 +5047,18 @@ typedef void (^BRAWCompletionHandler)(void);
         double defaultPositionOffsetX   = 0.0;
         double defaultPositionOffsetY   = 0.0;
         double defaultVideoRotation     = 0.0;
+        double defaultFrameReadoutTime  = 0.0;
+        uint8_t defaultFrameReadoutDirection = 0;
+        uint8_t defaultAdaptiveZoomMode = 0;
+        double defaultAdaptiveZoomWindow = 0.0;
+        double defaultMaxZoom           = 0.0;
+        double defaultLightRefractionCoefficient = 1.0;
+        double defaultAdditionalRotationPitch   = 0.0;
+        double defaultAdditionalRotationYaw     = 0.0;
+        double defaultAdditionalRotationRoll    = 0.0;
+        double defaultAdditionalTranslationX    = 0.0;
+        double defaultAdditionalTranslationY    = 0.0;
+        double defaultAdditionalTranslationZ    = 0.0;
         
         const char* getDefaultValuesResult = getDefaultValues(
                                                               [selectedGyroflowProjectData UTF8String],
@@ -4598,7 +5069,19 @@ typedef void (^BRAWCompletionHandler)(void);
                                                               &defaultHorizonRoll,
                                                               &defaultPositionOffsetX,
                                                               &defaultPositionOffsetY,
-                                                              &defaultVideoRotation
+                                                              &defaultVideoRotation,
+                                                              &defaultFrameReadoutTime,
+                                                              &defaultFrameReadoutDirection,
+                                                              &defaultAdaptiveZoomMode,
+                                                              &defaultAdaptiveZoomWindow,
+                                                              &defaultMaxZoom,
+                                                              &defaultLightRefractionCoefficient,
+                                                              &defaultAdditionalRotationPitch,
+                                                              &defaultAdditionalRotationYaw,
+                                                              &defaultAdditionalRotationRoll,
+                                                              &defaultAdditionalTranslationX,
+                                                              &defaultAdditionalTranslationY,
+                                                              &defaultAdditionalTranslationZ
                                                               );
         
         NSString *getDefaultValuesResultString = [NSString stringWithUTF8String:getDefaultValuesResult];
@@ -4685,6 +5168,19 @@ typedef void (^BRAWCompletionHandler)(void);
             [paramSetAPI setFloatValue:defaultPositionOffsetX toParameter:kCB_PositionOffsetX atTime:kCMTimeZero];
             [paramSetAPI setFloatValue:defaultPositionOffsetY toParameter:kCB_PositionOffsetY atTime:kCMTimeZero];
             [paramSetAPI setFloatValue:defaultVideoRotation toParameter:kCB_VideoRotation atTime:kCMTimeZero];
+            [paramSetAPI setBoolValue:YES toParameter:kCB_RollingShutterCorrection atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultFrameReadoutTime toParameter:kCB_FrameReadoutTime atTime:kCMTimeZero];
+            [paramSetAPI setIntValue:defaultFrameReadoutDirection + 1 toParameter:kCB_FrameReadoutDirection atTime:kCMTimeZero];
+            [paramSetAPI setIntValue:defaultAdaptiveZoomMode + 1 toParameter:kCB_AdaptiveZoomMode atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdaptiveZoomWindow toParameter:kCB_AdaptiveZoomWindow atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultMaxZoom toParameter:kCB_MaxZoom atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultLightRefractionCoefficient toParameter:kCB_LightRefractionCoefficient atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdditionalRotationPitch toParameter:kCB_AdditionalRotationPitch atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdditionalRotationYaw toParameter:kCB_AdditionalRotationYaw atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdditionalRotationRoll toParameter:kCB_AdditionalRotationRoll atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdditionalTranslationX toParameter:kCB_AdditionalTranslationX atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdditionalTranslationY toParameter:kCB_AdditionalTranslationY atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdditionalTranslationZ toParameter:kCB_AdditionalTranslationZ atTime:kCMTimeZero];
         } else {
             NSLog(@"[Gyroflow Toolbox Renderer] ERROR - Failed to get default values!");
         }
@@ -4877,21 +5373,66 @@ typedef void (^BRAWCompletionHandler)(void);
                                                    [path UTF8String]        // const char*
                                                    );
         
-        NSString *gyroflowProject = [NSString stringWithUTF8String: importResult];
+        NSString *importResultString = [NSString stringWithUTF8String: importResult];
         if (importResult) freeCString((char *)importResult);
 
-        //NSLog(@"[Gyroflow Toolbox Renderer] gyroflowProject: %@", gyroflowProject);
-            
+        //NSLog(@"[Gyroflow Toolbox Renderer] importResultString: %@", importResultString);
+
+        //---------------------------------------------------------
+        // Read the import result:
+        //---------------------------------------------------------
+        NSDictionary *importResultDictionary = nil;
+        NSData *importResultData = [importResultString dataUsingEncoding:NSUTF8StringEncoding];
+        if (importResultData) {
+            id importResultObject = [NSJSONSerialization JSONObjectWithData:importResultData options:0 error:nil];
+            if ([importResultObject isKindOfClass:[NSDictionary class]]) {
+                importResultDictionary = importResultObject;
+            }
+        }
+
+        NSString *importStatus = importResultDictionary[@"result"];
+        NSString *importMessage = importResultDictionary[@"message"];
+        NSString *gyroflowProject = nil;
+        if ([importResultDictionary[@"gyroflow_project"] isKindOfClass:[NSString class]]) {
+            gyroflowProject = importResultDictionary[@"gyroflow_project"];
+        }
+
+        NSString *detectedCamera = @"";
+        NSDictionary *camera = importResultDictionary[@"camera"];
+        if ([camera isKindOfClass:[NSDictionary class]]) {
+            detectedCamera = [[NSString stringWithFormat:@"%@ %@", camera[@"brand"] ?: @"", camera[@"model"] ?: @""] stringByTrimmingCharactersInSet:[NSCharacterSet whitespaceCharacterSet]];
+        }
+
+        NSLog(@"[Gyroflow Toolbox Renderer] Import Media File result: %@ (%@) - camera: %@, source: %@", importStatus, importMessage, detectedCamera, importResultDictionary[@"motion_data_file"]);
+
+        //---------------------------------------------------------
+        // Motion data without a matching lens profile can still
+        // be imported, but we need to warn the user:
+        //---------------------------------------------------------
+        BOOL isMissingLensProfile = [importStatus isEqualToString:@"no_lens_profile"];
+        if (isMissingLensProfile) {
+            NSLog(@"[Gyroflow Toolbox Renderer] WARNING - No matching lens profile was found for the imported Media File (camera: %@).", detectedCamera);
+        }
+
         //---------------------------------------------------------
         // Abort if there's no valid Gyroflow Project:
         //---------------------------------------------------------
-        if (gyroflowProject == nil || [gyroflowProject isEqualToString:@"FAIL"]) {
+        if (gyroflowProject == nil || importStatus == nil || [importStatus isEqualToString:@"cannot_open"] || [importStatus isEqualToString:@"no_telemetry"] || [importStatus isEqualToString:@"export_failed"]) {
             //---------------------------------------------------------
             // Close the Progress Alert:
             //---------------------------------------------------------
             [NSApp endSheet:self.progressAlert.window];
 
-            [self showAlertWithMessage:@"An error has occurred" info:@"Failed to generate a Gyroflow Project from the Media File.\n\nPlease check that the file you tried to import has gyroscope data."];
+            if ([importStatus isEqualToString:@"no_telemetry"]) {
+                NSString *cameraInfo = [detectedCamera isEqualToString:@""] ? @"" : [NSString stringWithFormat:@"\n\nDetected camera: %@", detectedCamera];
+                [self showAlertWithMessage:@"Missing Gyroscope Data" info:[NSString stringWithFormat:@"The Media File was opened, but no gyroscope data was found in it, or in any sidecar files next to it.%@", cameraInfo]];
+            } else if ([importStatus isEqualToString:@"export_failed"]) {
+                NSString *reason = importMessage ?: @"Unknown error.";
+                [self showAlertWithMessage:@"An error has occurred" info:[NSString stringWithFormat:@"The gyroscope data was loaded, but a Gyroflow Project couldn't be created from it.\n\n%@", reason]];
+            } else {
+                NSString *reason = importMessage ?: @"Unknown error.";
+                [self showAlertWithMessage:@"An error has occurred" info:[NSString stringWithFormat:@"Failed to open the Media File.\n\n%@", reason]];
+            }
             
             //---------------------------------------------------------
             // Trigger the Completion Handler:
@@ -4964,6 +5505,9 @@ typedef void (^BRAWCompletionHandler)(void);
             //---------------------------------------------------------
             NSString *message = @"Requires Synchronization in Gyroflow";
             NSString *info = @"The imported media file needs to be synchronized in Gyroflow before it can be used by Gyroflow Toolbox in Final Cut Pro.\n\nYou will be prompted to save the Gyroflow Project, and then we'll launch Gyroflow to open it.\n\nWhen you have finished synchronizing in Gyroflow, press COMMAND+S to save the project. Then back in Final Cut Pro press the 'Reload Gyroflow Project' button in the Inspector of the Gyroflow Toolbox effect.";
+            if (isMissingLensProfile) {
+                info = [info stringByAppendingString:@"\n\nNo matching lens profile was found for this camera, so you will also need to select a Lens Profile in Gyroflow."];
+            }
             [self showAlertWithMessage:message info:info];
             requiresGyroflowLaunch = YES;
         }
@@ -5096,6 +5640,18 @@ typedef void (^BRAWCompletionHandler)(void);
         double defaultPositionOffsetX   = 0.0;
         double defaultPositionOffsetY   = 0.0;
         double defaultVideoRotation     = 0.0;
+        double defaultFrameReadoutTime  = 0.0;
+        uint8_t defaultFrameReadoutDirection = 0;
+        uint8_t defaultAdaptiveZoomMode = 0;
+        double defaultAdaptiveZoomWindow = 0.0;
+        double defaultMaxZoom           = 0.0;
+        double defaultLightRefractionCoefficient = 1.0;
+        double defaultAdditionalRotationPitch   = 0.0;
+        double defaultAdditionalRotationYaw     = 0.0;
+        double defaultAdditionalRotationRoll    = 0.0;
+        double defaultAdditionalTranslationX    = 0.0;
+        double defaultAdditionalTranslationY    = 0.0;
+        double defaultAdditionalTranslationZ    = 0.0;
         
         const char* getDefaultValuesResult = getDefaultValues(
             [gyroflowProject UTF8String],
@@ -5106,7 +5662,19 @@ typedef void (^BRAWCompletionHandler)(void);
             &defaultHorizonRoll,
             &defaultPositionOffsetX,
             &defaultPositionOffsetY,
-            &defaultVideoRotation
+            &defaultVideoRotation,
+            &defaultFrameReadoutTime,
+            &defaultFrameReadoutDirection,
+            &defaultAdaptiveZoomMode,
+            &defaultAdaptiveZoomWindow,
+            &defaultMaxZoom,
+            &defaultLightRefractionCoefficient,
+            &defaultAdditionalRotationPitch,
+            &defaultAdditionalRotationYaw,
+            &defaultAdditionalRotationRoll,
+            &defaultAdditionalTranslationX,
+            &defaultAdditionalTranslationY,
+            &defaultAdditionalTranslationZ
         );
         
         NSString *getDefaultValuesResultString = [NSString stringWithUTF8String:getDefaultValuesResult];
@@ -5212,6 +5780,19 @@ typedef void (^BRAWCompletionHandler)(void);
             [paramSetAPI setFloatValue:defaultPositionOffsetX toParameter:kCB_PositionOffsetX atTime:kCMTimeZero];
             [paramSetAPI setFloatValue:defaultPositionOffsetY toParameter:kCB_PositionOffsetY atTime:kCMTimeZero];
             [paramSetAPI setFloatValue:defaultVideoRotation toParameter:kCB_VideoRotation atTime:kCMTimeZero];
+            [paramSetAPI setBoolValue:YES toParameter:kCB_RollingShutterCorrection atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultFrameReadoutTime toParameter:kCB_FrameReadoutTime atTime:kCMTimeZero];
+            [paramSetAPI setIntValue:defaultFrameReadoutDirection + 1 toParameter:kCB_FrameReadoutDirection atTime:kCMTimeZero];
+            [paramSetAPI setIntValue:defaultAdaptiveZoomMode + 1 toParameter:kCB_AdaptiveZoomMode atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdaptiveZoomWindow toParameter:kCB_AdaptiveZoomWindow atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultMaxZoom toParameter:kCB_MaxZoom atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultLightRefractionCoefficient toParameter:kCB_LightRefractionCoefficient atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdditionalRotationPitch toParameter:kCB_AdditionalRotationPitch atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdditionalRotationYaw toParameter:kCB_AdditionalRotationYaw atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdditionalRotationRoll toParameter:kCB_AdditionalRotationRoll atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdditionalTranslationX toParameter:kCB_AdditionalTranslationX atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdditionalTranslationY toParameter:kCB_AdditionalTranslationY atTime:kCMTimeZero];
+            [paramSetAPI setFloatValue:defaultAdditionalTranslationZ toParameter:kCB_AdditionalTranslationZ atTime:kCMTimeZero];
         } else {
             NSLog(@"[Gyroflow Toolbox Renderer] ERROR - Failed to get default values!");
         }
@@ -5265,7 +5846,7 @@ typedef void (^BRAWCompletionHandler)(void);
         if (lensProfileLoaded) freeCString((char *)lensProfileLoaded);
 
         //NSLog(@"[Gyroflow Toolbox Renderer] isLensProfileLoaded: %@", isLensProfileLoaded);
-        if (isLensProfileLoaded == nil || ![isLensProfileLoaded isEqualToString:@"YES"]) {
+        if (isMissingLensProfile || isLensProfileLoaded == nil || ![isLensProfileLoaded isEqualToString:@"YES"]) {
             //---------------------------------------------------------
             // Close the Progress Alert:
             //---------------------------------------------------------